@@ -0,0 +1,182 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, Changed, Wait};
+use atomig::Atom;
+use core::{
+    array,
+    future::Future,
+    ops::Index,
+    pin::Pin,
+    slice,
+    task::{Context, Poll},
+};
+use futures::stream::{FusedStream, Stream};
+
+/// Fixed-size array of async atomics, each one with its own waker.
+///
+/// Storing to an element wakes only the subscriber of that element.
+/// Each element is an [`AsyncAtomic`], so the whole [`AsyncAtomicRef`] API is available through [`get`](`Self::get`).
+#[derive(Debug)]
+pub struct AsyncAtomicArray<T: Atom, const N: usize> {
+    items: [AsyncAtomic<T>; N],
+}
+
+impl<T: Atom, const N: usize> AsyncAtomicArray<T, N> {
+    pub fn new(values: [T; N]) -> Self {
+        Self {
+            items: values.map(AsyncAtomic::new),
+        }
+    }
+
+    /// Create array with elements initialized by `f(index)`.
+    pub fn from_fn<F: FnMut(usize) -> T>(mut f: F) -> Self {
+        Self {
+            items: array::from_fn(|i| AsyncAtomic::new(f(i))),
+        }
+    }
+
+    /// Consume the array and return its values.
+    pub fn into_inner(self) -> [T; N] {
+        self.items.map(AsyncAtomic::into_inner)
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> &AsyncAtomic<T> {
+        &self.items[index]
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, AsyncAtomic<T>> {
+        self.items.iter()
+    }
+
+    pub fn load(&self, index: usize) -> T {
+        self.get(index).load()
+    }
+
+    /// Store value to element at `index` waking only its subscriber.
+    pub fn store(&self, index: usize, val: T) {
+        self.get(index).store(val)
+    }
+
+    pub fn swap(&self, index: usize, val: T) -> T {
+        self.get(index).swap(val)
+    }
+
+    pub fn fetch_update<F: FnMut(T) -> Option<T>>(&self, index: usize, f: F) -> Result<T, T> {
+        self.get(index).fetch_update(f)
+    }
+
+    /// Asynchronously wait for predicate on element at `index` to be `true`.
+    pub fn wait<F: FnMut(T) -> bool>(&self, index: usize, pred: F) -> Wait<&AsyncAtomic<T>, F> {
+        self.get(index).wait(pred)
+    }
+
+    /// Stream that yields element at `index` when it is changed.
+    ///
+    /// The current value is yielded on the first poll.
+    pub fn changed(&self, index: usize) -> Changed<&AsyncAtomic<T>>
+    where
+        T: PartialEq + Clone,
+    {
+        self.get(index).changed()
+    }
+
+    /// Stream that yields `(index, value)` when any element is changed.
+    ///
+    /// Current values of all elements are yielded first.
+    /// Elements are checked in round-robin order, so a frequently changing element doesn't starve others.
+    ///
+    /// *It occupies wakers of all elements, so it should not be mixed with per-element subscriptions.*
+    pub fn any_changed(&self) -> AnyChanged<'_, T, N>
+    where
+        T: PartialEq + Clone,
+    {
+        AnyChanged {
+            array: self,
+            prev: array::from_fn(|_| None),
+            next: 0,
+        }
+    }
+}
+
+impl<T: Atom + Default, const N: usize> Default for AsyncAtomicArray<T, N> {
+    fn default() -> Self {
+        Self::from_fn(|_| T::default())
+    }
+}
+
+impl<T: Atom, const N: usize> Index<usize> for AsyncAtomicArray<T, N> {
+    type Output = AsyncAtomic<T>;
+    fn index(&self, index: usize) -> &AsyncAtomic<T> {
+        self.get(index)
+    }
+}
+
+impl<'a, T: Atom, const N: usize> IntoIterator for &'a AsyncAtomicArray<T, N> {
+    type Item = &'a AsyncAtomic<T>;
+    type IntoIter = slice::Iter<'a, AsyncAtomic<T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Stream of changes of any element of [`AsyncAtomicArray`].
+pub struct AnyChanged<'a, T: Atom + PartialEq + Clone, const N: usize> {
+    pub array: &'a AsyncAtomicArray<T, N>,
+    /// Last yielded value of each element.
+    pub prev: [Option<T>; N],
+    /// Index of element to check first on the next poll.
+    pub next: usize,
+}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> Unpin for AnyChanged<'_, T, N> {}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> Drop for AnyChanged<'_, T, N> {
+    fn drop(&mut self) {
+        self.array.iter().for_each(AsyncAtomic::release_dropped);
+    }
+}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> Future for AnyChanged<'_, T, N> {
+    type Output = (usize, T);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        for k in 0..N {
+            let index = (this.next + k) % N;
+            if let Poll::Ready(value) = this
+                .array
+                .get(index)
+                .poll_changed(cx, &mut this.prev[index])
+            {
+                this.next = (index + 1) % N;
+                return Poll::Ready((index, value));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> Stream for AnyChanged<'_, T, N> {
+    type Item = (usize, T);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> FusedStream for AnyChanged<'_, T, N> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
@@ -1,15 +1,28 @@
-use crate::AsyncAtomic;
+use crate::{
+    timeout, AsyncAtomic, Budget, Cancellable, Counter, ExclusiveSubscriber, Lagged, Occupied,
+    ScopedSubscriber, Subscriber, Timeout, Timer,
+};
 use atomig::Atom;
 use core::{
-    future::Future,
+    convert::Infallible,
+    future::{Future, IntoFuture},
+    hint, mem,
     ops::Deref,
     pin::Pin,
     sync::atomic::Ordering,
     task::{Context, Poll},
+    time::Duration,
+};
+use futures::{
+    future::FusedFuture,
+    sink::Sink,
+    stream::{FusedStream, Stream},
 };
-use futures::stream::{FusedStream, Stream};
 use pin_project_lite::pin_project;
 
+/// Limit of exponential backoff while spinning, in powers of two.
+const MAX_BACKOFF_SHIFT: usize = 6;
+
 /// Generic reference to async atomic.
 ///
 /// Contains `async` methods which returns futures that wait for atomic value change.
@@ -23,22 +36,231 @@ pub trait AsyncAtomicRef {
     /// Get reference to original atomic structure.
     fn as_atomic(&self) -> &AsyncAtomic<Self::Item>;
 
+    /// Poll for predicate to be `true`.
+    ///
+    /// This is a low-level building block for custom futures and streams.
+    /// Registers waker from `cx` and then checks predicate on the current value.
+    fn poll_wait<F: FnMut(Self::Item) -> bool>(
+        &self,
+        cx: &mut Context<'_>,
+        mut pred: F,
+    ) -> Poll<()> {
+        let atomic = self.as_atomic();
+        atomic.register(cx.waker());
+        let value = atomic.value.load(Ordering::Acquire);
+        // TODO: Evaluate predicate on store to avoid spurious wake-ups.
+        if pred(value) {
+            atomic.release(cx.waker());
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Poll for value to be changed.
+    ///
+    /// This is a low-level building block for custom futures and streams.
+    /// Registers waker from `cx` and then compares the current value with `prev`.
+    /// If the value differs then `prev` is replaced with it and the value is returned.
+    /// `None` in `prev` means that nothing is seen yet, so the current value is returned immediately.
+    fn poll_changed(&self, cx: &mut Context<'_>, prev: &mut Option<Self::Item>) -> Poll<Self::Item>
+    where
+        Self::Item: PartialEq + Clone,
+    {
+        let atomic = self.as_atomic();
+        atomic.register(cx.waker());
+        let value = atomic.value.load(Ordering::Acquire);
+        if prev.replace(value.clone()).is_none_or(|prev| prev != value) {
+            Poll::Ready(value)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Check predicate on the current value once without registering waker.
+    ///
+    /// Returns the value if predicate is `true`. Allows to opportunistically check the condition before awaiting.
+    fn try_wait<F: FnOnce(Self::Item) -> bool>(&self, pred: F) -> Option<Self::Item>
+    where
+        Self::Item: Clone,
+    {
+        let value = self.as_atomic().load();
+        pred(value.clone()).then_some(value)
+    }
+
     /// Asynchronously wait for predicate to be `true`.
     fn wait<F: FnMut(Self::Item) -> bool>(&self, pred: F) -> Wait<&Self, F> {
-        Wait { inner: self, pred }
+        Wait {
+            inner: self,
+            pred,
+            spin: 0,
+            terminated: false,
+        }
+    }
+
+    /// Asynchronously wait for predicate to be `true`, failing instead of panicking if another task is waiting.
+    ///
+    /// Resolves with [`Occupied`] if the atomic rejects the waker according to [`WakerPolicy`](`crate::WakerPolicy`).
+    /// With policies that always register the waker it never fails.
+    fn checked_wait<F: FnMut(Self::Item) -> bool>(&self, pred: F) -> CheckedWait<&Self, F> {
+        CheckedWait {
+            inner: self,
+            pred,
+            registered: false,
+        }
+    }
+
+    /// Asynchronously wait for atomic to be in the specified state.
+    fn wait_state(&self, state: Self::Item) -> WaitState<&Self>
+    where
+        Self::Item: PartialEq,
+    {
+        WaitState { inner: self, state }
+    }
+
+    /// Asynchronously wait for predicate to be `true` for `count` consecutive observations.
+    ///
+    /// The current value is the first observation and each subsequent store is the next one,
+    /// so transient glitches in noisy values are filtered out without timers.
+    /// Stores that happened between polls are coalesced and observed as a single one.
+    fn wait_stable<F: FnMut(Self::Item) -> bool>(
+        &self,
+        pred: F,
+        count: usize,
+    ) -> WaitStable<&Self, F> {
+        WaitStable {
+            inner: self,
+            pred,
+            count,
+            streak: 0,
+            seen: None,
+        }
+    }
+
+    /// Asynchronously wait until value differs from `snapshot` and resolve with the new value.
+    ///
+    /// Unlike [`changed`](`Self::changed`) the last seen value is passed explicitly instead of being kept in a stream,
+    /// so the waiting loop can be stateless: `last = atomic.wait_changed_from(last).await`.
+    fn wait_changed_from(&self, snapshot: Self::Item) -> WaitChangedFrom<&Self>
+    where
+        Self::Item: PartialEq,
+    {
+        WaitChangedFrom {
+            inner: self,
+            snapshot,
+        }
+    }
+
+    /// Asynchronously wait while predicate is `true`, i.e. until it becomes `false`.
+    ///
+    /// This is a complement of [`wait`](`Self::wait`) like [`Condvar::wait_while`](https://doc.rust-lang.org/std/sync/struct.Condvar.html#method.wait_while).
+    fn wait_while<F: FnMut(Self::Item) -> bool>(&self, pred: F) -> WaitWhile<&Self, F> {
+        WaitWhile { inner: self, pred }
+    }
+
+    /// Asynchronously wait for predicate to be `true` but no longer than `duration`.
+    ///
+    /// Resolves with `Err(Elapsed)` if predicate hasn't become `true` in time.
+    fn wait_timeout<F: FnMut(Self::Item) -> bool, T: Timer + ?Sized>(
+        &self,
+        pred: F,
+        timer: &T,
+        duration: Duration,
+    ) -> Timeout<Wait<&Self, F>, T::Sleep> {
+        timeout(timer, duration, self.wait(pred))
+    }
+
+    /// Asynchronously wait for predicate to be `true` until `cancel` future completes.
+    ///
+    /// Resolves with `Err(Cancelled)` if cancelled first.
+    /// Accepts `&CancelToken` or any other future resolving with `()`.
+    fn wait_with_cancel<F: FnMut(Self::Item) -> bool, C: IntoFuture<Output = ()>>(
+        &self,
+        pred: F,
+        cancel: C,
+    ) -> Cancellable<Wait<&Self, F>, C::IntoFuture> {
+        Cancellable {
+            future: self.wait(pred),
+            cancel: cancel.into_future(),
+        }
+    }
+
+    /// Asynchronously wait until `map` returned `Some(x)` and then resolve with `x`.
+    ///
+    /// Allows to extract a value of a different type from the atomic while waiting.
+    fn wait_map<U, F: FnMut(Self::Item) -> Option<U>>(&self, map: F) -> WaitMap<&Self, F> {
+        WaitMap { inner: self, map }
     }
 
     /// Asynchronously wait until `map` returned `Some(x)` and then store `x` in atomic.
     ///
     /// This is an asynchronous version of [`fetch_update`][`AsyncAtomic::fetch_update`].
+    /// If the value was concurrently modified between `map` call and the store, `map` is retried immediately
+    /// with the new value, so the future suspends only when `map` actually returned `None`.
+    ///
+    /// If `map` panics the value is left unchanged and the waker of the panicking task is unregistered.
     fn wait_and_update<F: FnMut(Self::Item) -> Option<Self::Item>>(
         &self,
         map: F,
     ) -> WaitAndUpdate<&Self, F> {
-        WaitAndUpdate { inner: self, map }
+        WaitAndUpdate {
+            inner: self,
+            map,
+            terminated: false,
+        }
+    }
+
+    /// Asynchronously wait for predicate to be `true` and then atomically replace the value with `new`.
+    ///
+    /// Resolves with the replaced value. If `pred` panics the value is left unchanged.
+    fn wait_and_swap<F: FnMut(Self::Item) -> bool>(
+        &self,
+        pred: F,
+        new: Self::Item,
+    ) -> WaitAndSwap<&Self, F>
+    where
+        Self::Item: Clone,
+    {
+        WaitAndSwap {
+            inner: self,
+            pred,
+            new,
+        }
+    }
+
+    /// Asynchronously wait until value differs from default and then atomically replace it with default.
+    ///
+    /// Resolves with the taken value. This is the classic mailbox consume operation.
+    fn wait_and_take(&self) -> WaitAndSwap<&Self, fn(Self::Item) -> bool>
+    where
+        Self::Item: Default + PartialEq + Clone,
+    {
+        self.wait_and_swap(|x| x != Self::Item::default(), Self::Item::default())
+    }
+
+    /// Asynchronously wait until value is equal to `current` and then atomically replace it with `new`.
+    ///
+    /// This is an asynchronous version of [`compare_exchange`][`AsyncAtomic::compare_exchange`]
+    /// that retries on every notification until succeeded.
+    fn wait_compare_exchange(
+        &self,
+        current: Self::Item,
+        new: Self::Item,
+    ) -> WaitCompareExchange<&Self>
+    where
+        Self::Item: Clone,
+    {
+        WaitCompareExchange {
+            inner: self,
+            current,
+            new,
+        }
     }
 
     /// Convert subscriber into stream that yields when value is changed.
+    ///
+    /// The current value is yielded on the first poll, so the stream always starts with the present state
+    /// (like `tokio::sync::watch` receiver wrapped into a stream).
     fn changed(self) -> Changed<Self>
     where
         Self: Sized,
@@ -49,6 +271,118 @@ pub trait AsyncAtomicRef {
             prev: None,
         }
     }
+
+    /// Convert subscriber into stream that yields when value is changed according to custom comparator.
+    ///
+    /// `eq(prev, value)` should return `true` if the values are considered equal, in that case `value` is skipped.
+    /// Values are compared with the last yielded one, so a slow drift is still detected.
+    ///
+    /// For example, `changed_by(|a, b| a & MASK == b & MASK)` watches only bits from `MASK`.
+    ///
+    /// *This is not a bitmask-scoped subscription: stores that don't modify masked bits still wake the task,
+    /// and the stream returns to waiting without yielding.*
+    /// *Stores don't know which bits the waiter is interested in, so wake-ups can't be filtered by mask.*
+    ///
+    /// Like [`changed`](`Self::changed`) the current value is yielded on the first poll.
+    fn changed_by<F: FnMut(&Self::Item, &Self::Item) -> bool>(self, eq: F) -> ChangedBy<Self, F>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        ChangedBy {
+            inner: self,
+            prev: None,
+            eq,
+        }
+    }
+
+    /// Create [`Subscriber`] that can check for updates without awaiting.
+    fn subscribe(self) -> Subscriber<Self>
+    where
+        Self: Sized,
+    {
+        Subscriber::new(self)
+    }
+
+    /// Create [`ExclusiveSubscriber`] if there is no other one for the atomic.
+    ///
+    /// Returns `None` if the atomic already has an exclusive subscriber.
+    /// The claim is released when the subscriber is dropped.
+    /// This gives a runtime check that the single waker isn't shared by accident,
+    /// as long as all subscriptions are made this way.
+    fn try_subscribe(self) -> Option<ExclusiveSubscriber<Self>>
+    where
+        Self: Sized,
+    {
+        ExclusiveSubscriber::try_new(self)
+    }
+
+    /// Create [`BlockingSubscriber`](`crate::BlockingSubscriber`) that iterates over changes blocking the current thread.
+    #[cfg(feature = "std")]
+    fn subscribe_blocking(self) -> crate::BlockingSubscriber<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq + Clone,
+    {
+        crate::BlockingSubscriber::<Self>::new(self)
+    }
+
+    /// Create [`ScopedSubscriber`] that restores the previously registered waker on drop.
+    fn subscribe_scoped(self) -> ScopedSubscriber<Self>
+    where
+        Self: Sized,
+    {
+        ScopedSubscriber::new(self)
+    }
+
+    /// Convert subscriber into stream that yields `(previous, current)` pairs when value is changed.
+    ///
+    /// The value at the moment of stream creation is used as initial `previous` value, so nothing is yielded until the first change.
+    /// Values stored between polls are not observed, so a transition may skip intermediate states.
+    fn transitions(self) -> Transitions<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq + Clone,
+    {
+        let prev = self.as_atomic().load();
+        Transitions { inner: self, prev }
+    }
+
+    /// Convert counter into stream that yields the amount it advanced since the previous yield.
+    ///
+    /// The value at the moment of stream creation is used as the starting point.
+    /// Unlike [`changed`](`Self::changed`) no increments are lost when several stores are coalesced between polls,
+    /// they are summed into a single delta instead (as long as the counter doesn't wrap around completely).
+    fn deltas(self) -> Deltas<Self>
+    where
+        Self: Sized,
+        Self::Item: Counter,
+    {
+        let prev = self.as_atomic().load();
+        Deltas { inner: self, prev }
+    }
+
+    /// Create a view of the atomic whose methods operate on `map(value)` instead of the raw value.
+    ///
+    /// It is useful to expose a typed view (e.g. an enum decoded from an integer) without leaking the raw representation.
+    fn mapped<U, F: Fn(Self::Item) -> U>(self, map: F) -> Mapped<Self, F>
+    where
+        Self: Sized,
+    {
+        Mapped { inner: self, map }
+    }
+
+    /// Convert subscriber into stream that yields on every store, even if the stored value is equal to the previous one.
+    ///
+    /// Unlike [`changed`](`Self::changed`) the current value is not yielded, only the values stored after stream creation.
+    /// Stores made between polls are coalesced into a single item containing the latest value.
+    fn updates(self) -> Updates<Self>
+    where
+        Self: Sized,
+    {
+        let seen = self.as_atomic().generation();
+        Updates { inner: self, seen }
+    }
 }
 
 impl<T: Atom> AsyncAtomicRef for AsyncAtomic<T> {
@@ -58,6 +392,38 @@ impl<T: Atom> AsyncAtomicRef for AsyncAtomic<T> {
     }
 }
 
+/// Awaiting a reference to atomic resolves with the value after the next store.
+impl<'a, T: Atom> IntoFuture for &'a AsyncAtomic<T> {
+    type Output = T;
+    type IntoFuture = Updates<&'a AsyncAtomic<T>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.updates()
+    }
+}
+
+/// Reference to atomic is a producer handle, every sent item is stored and the subscriber is woken.
+///
+/// Sink never fails and is always ready, so a stream can be forwarded into atomic using
+/// `stream.map(Ok).forward(&atomic)`.
+impl<T: Atom> Sink<T> for &AsyncAtomic<T> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.store(item);
+        Ok(())
+    }
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
 impl<R: Deref<Target: AsyncAtomicRef>> AsyncAtomicRef for R {
     type Item = <R::Target as AsyncAtomicRef>::Item;
     fn as_atomic(&self) -> &AsyncAtomic<Self::Item> {
@@ -69,80 +435,185 @@ impl<T: Atom + PartialEq> AsyncAtomic<T> {}
 
 /// Future to wait for specific value.
 pub struct Wait<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> {
-    pub inner: R,
-    pub pred: F,
+    inner: R,
+    pred: F,
+    /// Number of checks to make before registering waker, see [`with_spin`](`Self::with_spin`).
+    spin: usize,
+    /// Whether the future has already completed.
+    terminated: bool,
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Wait<R, F> {
+    /// Check predicate up to `spin` times with exponential backoff before registering waker and returning `Pending`.
+    ///
+    /// When the producer is expected to update the atomic within a fraction of microsecond,
+    /// spinning is cheaper than the full suspend and wake round trip. Spinning is done on every poll.
+    pub fn with_spin(mut self, spin: usize) -> Self {
+        self.spin = spin;
+        self
+    }
 }
 
 impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Unpin for Wait<R, F> {}
 
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Drop for Wait<R, F> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
 impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Future for Wait<R, F> {
     type Output = ();
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let atomic = self.inner.as_atomic();
-        atomic.waker.register(cx.waker());
-        let value = atomic.value.load(Ordering::Acquire);
-        // TODO: Evaluate predicate on store to avoid spurious wake-ups.
-        if (self.pred)(value) {
-            Poll::Ready(())
+        let this = &mut *self;
+        for i in 0..this.spin {
+            if (this.pred)(this.inner.as_atomic().load()) {
+                this.terminated = true;
+                return Poll::Ready(());
+            }
+            for _ in 0..(1 << i.min(MAX_BACKOFF_SHIFT)) {
+                hint::spin_loop();
+            }
+        }
+        let poll = this.inner.poll_wait(cx, &mut this.pred);
+        this.terminated = poll.is_ready();
+        poll
+    }
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> FusedFuture for Wait<R, F> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+/// Future to wait for specific value that fails if another task is waiting, see [`checked_wait`](`AsyncAtomicRef::checked_wait`).
+pub struct CheckedWait<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> {
+    inner: R,
+    pred: F,
+    /// Whether the waker was registered, so the atomic is occupied by this future.
+    registered: bool,
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Unpin for CheckedWait<R, F> {}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Drop for CheckedWait<R, F> {
+    fn drop(&mut self) {
+        if self.registered {
+            self.inner.as_atomic().release_dropped();
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Future for CheckedWait<R, F> {
+    type Output = Result<(), Occupied>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let atomic = this.inner.as_atomic();
+        atomic.try_register(cx.waker())?;
+        this.registered = true;
+        if (this.pred)(atomic.value.load(Ordering::Acquire)) {
+            atomic.release(cx.waker());
+            this.registered = false;
+            Poll::Ready(Ok(()))
         } else {
             Poll::Pending
         }
     }
 }
 
-pin_project! {
-    /// Future to wait and update an atomic value.
-    pub struct WaitAndUpdate<R: AsyncAtomicRef, F: FnMut(R::Item) -> Option<R::Item>> {
-        pub inner: R,
-        pub map: F,
+/// Future to wait for specific state.
+pub struct WaitState<R: AsyncAtomicRef<Item: PartialEq>> {
+    pub inner: R,
+    pub state: R::Item,
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq>> Unpin for WaitState<R> {}
+
+impl<R: AsyncAtomicRef<Item: PartialEq>> Drop for WaitState<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
     }
 }
 
-impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> Option<R::Item>> Future for WaitAndUpdate<R, F> {
-    type Output = R::Item;
+impl<R: AsyncAtomicRef<Item: PartialEq>> Future for WaitState<R> {
+    type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let mut this = self.project();
+        self.inner.poll_wait(cx, |x| x == self.state)
+    }
+}
+
+/// Future to wait for predicate to hold for several consecutive observations.
+pub struct WaitStable<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> {
+    pub inner: R,
+    pub pred: F,
+    /// Required number of consecutive observations.
+    pub count: usize,
+    /// Number of consecutive observations the predicate held for.
+    pub streak: usize,
+    /// Generation of the last observation.
+    pub seen: Option<usize>,
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Unpin for WaitStable<R, F> {}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Drop for WaitStable<R, F> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Future for WaitStable<R, F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
         let atomic = this.inner.as_atomic();
-        atomic.waker.register(cx.waker());
-        match atomic
-            .value
-            .fetch_update(Ordering::AcqRel, Ordering::Acquire, &mut this.map)
-        {
-            Ok(x) => Poll::Ready(x),
-            Err(_) => Poll::Pending,
+        atomic.register(cx.waker());
+        let generation = atomic.generation();
+        if this.seen != Some(generation) {
+            this.seen = Some(generation);
+            if (this.pred)(atomic.value.load(Ordering::Acquire)) {
+                this.streak += 1;
+            } else {
+                this.streak = 0;
+            }
+        }
+        if this.streak >= this.count {
+            atomic.release(cx.waker());
+            Poll::Ready(())
+        } else {
+            Poll::Pending
         }
     }
 }
 
-/// Stream that yields value when it change.
-pub struct Changed<R: AsyncAtomicRef<Item: PartialEq + Clone>> {
+/// Future to wait for value to differ from snapshot.
+pub struct WaitChangedFrom<R: AsyncAtomicRef<Item: PartialEq>> {
     pub inner: R,
-    pub prev: Option<R::Item>,
+    pub snapshot: R::Item,
 }
 
-impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Deref for Changed<R> {
-    type Target = R;
-    fn deref(&self) -> &Self::Target {
-        &self.inner
+impl<R: AsyncAtomicRef<Item: PartialEq>> Unpin for WaitChangedFrom<R> {}
+
+impl<R: AsyncAtomicRef<Item: PartialEq>> Drop for WaitChangedFrom<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
     }
 }
 
-impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Unpin for Changed<R> {}
-
-impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Future for Changed<R> {
+impl<R: AsyncAtomicRef<Item: PartialEq>> Future for WaitChangedFrom<R> {
     type Output = R::Item;
 
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let atomic = self.inner.as_atomic();
-        atomic.waker.register(cx.waker());
+        atomic.register(cx.waker());
         let value = atomic.value.load(Ordering::Acquire);
-        if self
-            .prev
-            .replace(value.clone())
-            .is_none_or(|prev| prev != value)
-        {
+        if value != self.snapshot {
+            atomic.release(cx.waker());
             Poll::Ready(value)
         } else {
             Poll::Pending
@@ -150,15 +621,692 @@ impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Future for Changed<R> {
     }
 }
 
-impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Stream for Changed<R> {
-    type Item = R::Item;
+/// Future to wait while predicate holds.
+pub struct WaitWhile<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> {
+    pub inner: R,
+    pub pred: F,
+}
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<R::Item>> {
-        self.poll(cx).map(Some)
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Unpin for WaitWhile<R, F> {}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Drop for WaitWhile<R, F> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
     }
 }
 
-impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> FusedStream for Changed<R> {
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> bool> Future for WaitWhile<R, F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.inner.poll_wait(cx, |x| !(this.pred)(x))
+    }
+}
+
+/// Future to wait for value that can be mapped.
+pub struct WaitMap<R: AsyncAtomicRef, F> {
+    pub inner: R,
+    pub map: F,
+}
+
+impl<R: AsyncAtomicRef, F> Unpin for WaitMap<R, F> {}
+
+impl<R: AsyncAtomicRef, F> Drop for WaitMap<R, F> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef, U, F: FnMut(R::Item) -> Option<U>> Future for WaitMap<R, F> {
+    type Output = U;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let atomic = this.inner.as_atomic();
+        atomic.register(cx.waker());
+        let value = atomic.value.load(Ordering::Acquire);
+        match (this.map)(value) {
+            Some(x) => {
+                atomic.release(cx.waker());
+                Poll::Ready(x)
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Unregisters waker if dropped, i.e. if user closure panicked while the guard was alive.
+///
+/// Otherwise the atomic would keep the waker of the unwinding task.
+struct UnregisterOnPanic<'a, T: Atom>(&'a AsyncAtomic<T>);
+
+impl<T: Atom> Drop for UnregisterOnPanic<'_, T> {
+    fn drop(&mut self) {
+        self.0.unregister();
+    }
+}
+
+pin_project! {
+    /// Future to wait and update an atomic value.
+    pub struct WaitAndUpdate<R: AsyncAtomicRef, F: FnMut(R::Item) -> Option<R::Item>> {
+        inner: R,
+        map: F,
+        // Whether the future has already completed.
+        terminated: bool,
+    }
+
+    impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> Option<R::Item>> PinnedDrop for WaitAndUpdate<R, F> {
+        fn drop(this: Pin<&mut Self>) {
+            this.inner.as_atomic().release_dropped();
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> Option<R::Item>> Future for WaitAndUpdate<R, F> {
+    type Output = R::Item;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let atomic = this.inner.as_atomic();
+        atomic.register(cx.waker());
+        let guard = UnregisterOnPanic(atomic);
+        let result = atomic
+            .value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, &mut this.map);
+        mem::forget(guard);
+        match result {
+            Ok(x) => {
+                atomic.notify();
+                *this.terminated = true;
+                atomic.release(cx.waker());
+                Poll::Ready(x)
+            }
+            Err(_) => Poll::Pending,
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef, F: FnMut(R::Item) -> Option<R::Item>> FusedFuture for WaitAndUpdate<R, F> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+/// Future to wait for value and replace it.
+pub struct WaitAndSwap<R: AsyncAtomicRef<Item: Clone>, F: FnMut(R::Item) -> bool> {
+    pub inner: R,
+    pub pred: F,
+    pub new: R::Item,
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(R::Item) -> bool> Unpin for WaitAndSwap<R, F> {}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(R::Item) -> bool> Drop for WaitAndSwap<R, F> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(R::Item) -> bool> Future for WaitAndSwap<R, F> {
+    type Output = R::Item;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let atomic = this.inner.as_atomic();
+        atomic.register(cx.waker());
+        let guard = UnregisterOnPanic(atomic);
+        let result = atomic
+            .value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |x| {
+                (this.pred)(x).then(|| this.new.clone())
+            });
+        mem::forget(guard);
+        match result {
+            Ok(x) => {
+                atomic.notify();
+                atomic.release(cx.waker());
+                Poll::Ready(x)
+            }
+            Err(_) => Poll::Pending,
+        }
+    }
+}
+
+/// Future to wait for specific value and replace it.
+pub struct WaitCompareExchange<R: AsyncAtomicRef<Item: Clone>> {
+    pub inner: R,
+    pub current: R::Item,
+    pub new: R::Item,
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>> Unpin for WaitCompareExchange<R> {}
+
+impl<R: AsyncAtomicRef<Item: Clone>> Drop for WaitCompareExchange<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>> Future for WaitCompareExchange<R> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let atomic = self.inner.as_atomic();
+        atomic.register(cx.waker());
+        match atomic.value.compare_exchange(
+            self.current.clone(),
+            self.new.clone(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                atomic.notify();
+                atomic.release(cx.waker());
+                Poll::Ready(())
+            }
+            Err(_) => Poll::Pending,
+        }
+    }
+}
+
+/// Stream that yields value when it change.
+pub struct Changed<R: AsyncAtomicRef<Item: PartialEq + Clone>> {
+    inner: R,
+    prev: Option<R::Item>,
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Deref for Changed<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Changed<R> {
+    /// Return the current value if it differs from the last yielded one, without registering waker.
+    ///
+    /// The returned value is considered yielded, so the stream won't yield it again.
+    pub fn try_changed(&mut self) -> Option<R::Item> {
+        let value = self.inner.as_atomic().load();
+        if self
+            .prev
+            .replace(value.clone())
+            .is_none_or(|prev| prev != value)
+        {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Yield to the executor after `limit` consecutive changes, see [`Budget`].
+    pub fn with_budget(self, limit: usize) -> Budget<Self> {
+        Budget::new(self, limit)
+    }
+
+    /// Attach sequence number to each yielded value.
+    ///
+    /// Numbers start from zero and are incremented by one for every item,
+    /// so downstream consumers can detect reordering or lost items.
+    pub fn with_sequence(self) -> WithSequence<R> {
+        WithSequence {
+            inner: self,
+            seq: 0,
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Unpin for Changed<R> {}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Drop for Changed<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Future for Changed<R> {
+    type Output = R::Item;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.inner.poll_changed(cx, &mut this.prev)
+    }
+}
+
+/// Each poll as a future resolves with the next change, so it never terminates.
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> FusedFuture for Changed<R> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Stream for Changed<R> {
+    type Item = R::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<R::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> FusedStream for Changed<R> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Stream that yields changed values along with their sequence numbers.
+pub struct WithSequence<R: AsyncAtomicRef<Item: PartialEq + Clone>> {
+    pub inner: Changed<R>,
+    /// Sequence number of the next item.
+    pub seq: u64,
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Unpin for WithSequence<R> {}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Future for WithSequence<R> {
+    type Output = (u64, R::Item);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        Pin::new(&mut this.inner).poll(cx).map(|value| {
+            let seq = this.seq;
+            this.seq += 1;
+            (seq, value)
+        })
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Stream for WithSequence<R> {
+    type Item = (u64, R::Item);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> FusedStream for WithSequence<R> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Stream that yields `(previous, current)` pairs when value change.
+pub struct Transitions<R: AsyncAtomicRef<Item: PartialEq + Clone>> {
+    pub inner: R,
+    pub prev: R::Item,
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Deref for Transitions<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Unpin for Transitions<R> {}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Drop for Transitions<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Future for Transitions<R> {
+    type Output = (R::Item, R::Item);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let atomic = self.inner.as_atomic();
+        atomic.register(cx.waker());
+        let value = atomic.value.load(Ordering::Acquire);
+        if self.prev != value {
+            let prev = mem::replace(&mut self.prev, value.clone());
+            Poll::Ready((prev, value))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Stream for Transitions<R> {
+    type Item = (R::Item, R::Item);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> FusedStream for Transitions<R> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Stream of counter increments.
+pub struct Deltas<R: AsyncAtomicRef<Item: Counter>> {
+    pub inner: R,
+    pub prev: R::Item,
+}
+
+impl<R: AsyncAtomicRef<Item: Counter>> Deref for Deltas<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Counter>> Unpin for Deltas<R> {}
+
+impl<R: AsyncAtomicRef<Item: Counter>> Drop for Deltas<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Counter>> Future for Deltas<R> {
+    type Output = R::Item;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let atomic = self.inner.as_atomic();
+        atomic.register(cx.waker());
+        let value = atomic.value.load(Ordering::Acquire);
+        if self.prev != value {
+            let prev = mem::replace(&mut self.prev, value);
+            Poll::Ready(value.delta(prev))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Counter>> Stream for Deltas<R> {
+    type Item = R::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Counter>> FusedStream for Deltas<R> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Stream that yields value when it change according to custom comparator.
+pub struct ChangedBy<R: AsyncAtomicRef<Item: Clone>, F: FnMut(&R::Item, &R::Item) -> bool> {
+    pub inner: R,
+    pub prev: Option<R::Item>,
+    pub eq: F,
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(&R::Item, &R::Item) -> bool> Deref
+    for ChangedBy<R, F>
+{
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(&R::Item, &R::Item) -> bool> Unpin
+    for ChangedBy<R, F>
+{
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(&R::Item, &R::Item) -> bool> Drop
+    for ChangedBy<R, F>
+{
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(&R::Item, &R::Item) -> bool> Future
+    for ChangedBy<R, F>
+{
+    type Output = R::Item;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let atomic = this.inner.as_atomic();
+        atomic.register(cx.waker());
+        let value = atomic.value.load(Ordering::Acquire);
+        if let Some(prev) = &this.prev {
+            if (this.eq)(prev, &value) {
+                return Poll::Pending;
+            }
+        }
+        this.prev = Some(value.clone());
+        Poll::Ready(value)
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(&R::Item, &R::Item) -> bool> Stream
+    for ChangedBy<R, F>
+{
+    type Item = R::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<R::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: Clone>, F: FnMut(&R::Item, &R::Item) -> bool> FusedStream
+    for ChangedBy<R, F>
+{
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// View of an atomic that maps its value into another type.
+pub struct Mapped<R: AsyncAtomicRef, F> {
+    pub inner: R,
+    pub map: F,
+}
+
+impl<R: AsyncAtomicRef, U, F: Fn(R::Item) -> U> Mapped<R, F> {
+    /// Load current value and map it.
+    pub fn load(&self) -> U {
+        (self.map)(self.inner.as_atomic().load())
+    }
+
+    /// Asynchronously wait for predicate on mapped value to be `true`.
+    pub fn wait<'a, P: FnMut(U) -> bool + 'a>(
+        &'a self,
+        mut pred: P,
+    ) -> Wait<&'a R, impl FnMut(R::Item) -> bool + 'a> {
+        self.inner.wait(move |x| pred((self.map)(x)))
+    }
+
+    /// Convert view into stream that yields mapped value when it is changed.
+    ///
+    /// Values are compared after mapping, so changes of the raw value that don't affect mapped one are skipped.
+    pub fn changed(self) -> MappedChanged<R, F, U>
+    where
+        U: PartialEq + Clone,
+    {
+        MappedChanged {
+            inner: self,
+            prev: None,
+        }
+    }
+}
+
+/// Stream that yields mapped value when it change.
+pub struct MappedChanged<R: AsyncAtomicRef, F, U> {
+    pub inner: Mapped<R, F>,
+    pub prev: Option<U>,
+}
+
+impl<R: AsyncAtomicRef, F, U> Unpin for MappedChanged<R, F, U> {}
+
+impl<R: AsyncAtomicRef, F, U> Drop for MappedChanged<R, F, U> {
+    fn drop(&mut self) {
+        self.inner.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef, U: PartialEq + Clone, F: Fn(R::Item) -> U> Future
+    for MappedChanged<R, F, U>
+{
+    type Output = U;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let atomic = self.inner.inner.as_atomic();
+        atomic.register(cx.waker());
+        let value = (self.inner.map)(atomic.value.load(Ordering::Acquire));
+        if self
+            .prev
+            .replace(value.clone())
+            .is_none_or(|prev| prev != value)
+        {
+            Poll::Ready(value)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef, U: PartialEq + Clone, F: Fn(R::Item) -> U> Stream
+    for MappedChanged<R, F, U>
+{
+    type Item = U;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<U>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef, U: PartialEq + Clone, F: Fn(R::Item) -> U> FusedStream
+    for MappedChanged<R, F, U>
+{
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Stream that yields value on every store.
+pub struct Updates<R: AsyncAtomicRef> {
+    pub inner: R,
+    /// Last seen [`generation`](`AsyncAtomic::generation`).
+    pub seen: usize,
+}
+
+impl<R: AsyncAtomicRef> Deref for Updates<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef> Updates<R> {
+    /// Report missed stores instead of silently coalescing them.
+    ///
+    /// If `n` stores were made between polls then `Err(Lagged(n - 1))` is yielded first
+    /// followed by the latest value, like `tokio::sync::broadcast` receiver does.
+    pub fn with_lag(self) -> LaggedUpdates<R> {
+        LaggedUpdates { inner: self }
+    }
+
+    /// Yield to the executor after `limit` consecutive updates, see [`Budget`].
+    pub fn with_budget(self, limit: usize) -> Budget<Self> {
+        Budget::new(self, limit)
+    }
+}
+
+impl<R: AsyncAtomicRef> Unpin for Updates<R> {}
+
+impl<R: AsyncAtomicRef> Drop for Updates<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef> Future for Updates<R> {
+    type Output = R::Item;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let atomic = self.inner.as_atomic();
+        atomic.register(cx.waker());
+        let generation = atomic.generation();
+        if generation != self.seen {
+            let value = atomic.value.load(Ordering::Acquire);
+            self.seen = generation;
+            Poll::Ready(value)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef> Stream for Updates<R> {
+    type Item = R::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<R::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef> FusedStream for Updates<R> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Stream of updates that reports the number of missed stores.
+pub struct LaggedUpdates<R: AsyncAtomicRef> {
+    pub inner: Updates<R>,
+}
+
+impl<R: AsyncAtomicRef> Deref for LaggedUpdates<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner.inner
+    }
+}
+
+impl<R: AsyncAtomicRef> Unpin for LaggedUpdates<R> {}
+
+impl<R: AsyncAtomicRef> Drop for LaggedUpdates<R> {
+    fn drop(&mut self) {
+        self.inner.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef> Future for LaggedUpdates<R> {
+    type Output = Result<R::Item, Lagged>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Updates { inner, seen } = &mut self.inner;
+        let atomic = inner.as_atomic();
+        atomic.register(cx.waker());
+        let generation = atomic.generation();
+        match generation.wrapping_sub(*seen) {
+            0 => Poll::Pending,
+            1 => {
+                let value = atomic.value.load(Ordering::Acquire);
+                *seen = generation;
+                Poll::Ready(Ok(value))
+            }
+            n => {
+                *seen = generation.wrapping_sub(1);
+                Poll::Ready(Err(Lagged(n - 1)))
+            }
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef> Stream for LaggedUpdates<R> {
+    type Item = Result<R::Item, Lagged>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef> FusedStream for LaggedUpdates<R> {
     fn is_terminated(&self) -> bool {
         false
     }
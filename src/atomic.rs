@@ -1,32 +1,290 @@
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
 use atomig::{
     impls::{PrimitiveAtom, PrimitiveAtomInteger, PrimitiveAtomLogic},
     Atom, AtomInteger, AtomLogic, Atomic as BasicAtomic,
 };
-use core::sync::atomic::Ordering;
+use core::{
+    fmt,
+    sync::atomic::{fence, AtomicBool, AtomicU8, AtomicUsize, Ordering},
+    task::Waker,
+};
 use futures::task::AtomicWaker;
 
+/// What happens when a task registers its waker while another task is already waiting on [`AsyncAtomic`].
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum WakerPolicy {
+    /// The latest waker wins, the previous waiter is not woken anymore.
+    #[default]
+    Replace = 0,
+    /// The previous waiter keeps its place.
+    ///
+    /// [`try_register`](`AsyncAtomic::try_register`) and [`checked_wait`](`crate::AsyncAtomicRef::checked_wait`) return [`Occupied`],
+    /// while other futures provided by the crate, whose outputs cannot carry the error, panic with it.
+    Reject = 1,
+    /// Panic like [`Reject`](`Self::Reject`) in debug builds and [`Replace`](`Self::Replace`) otherwise.
+    DebugPanic = 2,
+    /// The latest waker wins, and the displaced waker is woken once so that its future re-polls instead of hanging.
+    ///
+    /// *If two tasks keep waiting concurrently they keep displacing and waking each other,
+    /// so the misuse shows up as busy polling rather than a silent hang.*
+    WakeDisplaced = 3,
+}
+
+impl WakerPolicy {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Reject,
+            2 => Self::DebugPanic,
+            3 => Self::WakeDisplaced,
+            _ => Self::Replace,
+        }
+    }
+}
+
+/// Error returned when another task is already waiting on the atomic, see [`WakerPolicy`].
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Occupied;
+
+impl fmt::Display for Occupied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "another task is already waiting on the atomic")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Occupied {}
+
 /// Atomic value that also contains [`Waker`](`core::task::Waker`) to notify subscriber asynchronously.
 ///
 /// *There is only a single waker, so there should be only single subscription at a time.*
-/// *Otherwise older subscriptions will not receive updates anymore.*
+/// *Otherwise older subscriptions will not receive updates anymore, unless configured by [`WakerPolicy`].*
 #[derive(Default, Debug)]
 pub struct AsyncAtomic<T: Atom> {
     pub(crate) value: BasicAtomic<T>,
+    pub(crate) generation: AtomicUsize,
     pub(crate) waker: AtomicWaker,
+    /// Whether the waker was registered since the last wake.
+    armed: AtomicBool,
+    /// [`WakerPolicy`] as `u8`.
+    policy: AtomicU8,
+    /// Whether the atomic has [`ExclusiveSubscriber`](`crate::ExclusiveSubscriber`).
+    pub(crate) exclusive: AtomicBool,
 }
 
 impl<T: Atom> AsyncAtomic<T> {
     pub fn new(value: T) -> Self {
         Self {
             value: BasicAtomic::new(value),
+            generation: AtomicUsize::new(0),
             waker: AtomicWaker::new(),
+            armed: AtomicBool::new(false),
+            policy: AtomicU8::new(WakerPolicy::Replace as u8),
+            exclusive: AtomicBool::new(false),
         }
     }
 
     pub const fn from_impl(repr: <T::Repr as PrimitiveAtom>::Impl) -> Self {
         Self {
             value: BasicAtomic::from_impl(repr),
+            generation: AtomicUsize::new(0),
             waker: AtomicWaker::new(),
+            armed: AtomicBool::new(false),
+            policy: AtomicU8::new(WakerPolicy::Replace as u8),
+            exclusive: AtomicBool::new(false),
+        }
+    }
+
+    /// Consume the atomic and return its value.
+    pub fn into_inner(self) -> T {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Take the value out of the atomic if the [`Arc`] is the only reference to it.
+    ///
+    /// Otherwise the [`Arc`] is returned back.
+    #[cfg(feature = "alloc")]
+    pub fn try_unwrap(this: Arc<Self>) -> Result<T, Arc<Self>> {
+        Arc::try_unwrap(this).map(Self::into_inner)
+    }
+
+    /// Access the value mutably through unique reference.
+    ///
+    /// There are no subscribers while the atomic is borrowed mutably, so nobody is woken,
+    /// but [`generation`](`Self::generation`) is still incremented.
+    pub fn with_mut<R, F: FnOnce(&mut T) -> R>(&mut self, f: F) -> R {
+        let mut value = self.value.load(Ordering::Relaxed);
+        let ret = f(&mut value);
+        self.value.store(value, Ordering::Relaxed);
+        let generation = self.generation.get_mut();
+        *generation = generation.wrapping_add(1);
+        ret
+    }
+
+    /// Number of modifications made to the atomic so far (wrapping on overflow).
+    ///
+    /// It is incremented on every store even if the stored value is equal to the previous one.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Whether a waker was registered since the last wake.
+    ///
+    /// Producers can use it to skip expensive preparation of a value nobody listens to.
+    /// It is only a hint: a subscriber that was woken and not yet polled again is not counted as waiting.
+    ///
+    /// With policies that reject a competing waiter the flag is cleared when a wait completes or is dropped.
+    /// Otherwise, e.g. with default [`WakerPolicy::Replace`], it stays set until the next store,
+    /// even if the registering future has completed or was dropped since.
+    pub fn has_waiter(&self) -> bool {
+        self.armed.load(Ordering::Acquire)
+    }
+
+    /// Set policy for a waker registered while another task is waiting.
+    pub fn with_waker_policy(self, policy: WakerPolicy) -> Self {
+        self.set_waker_policy(policy);
+        self
+    }
+
+    /// Set policy for a waker registered while another task is waiting.
+    ///
+    /// Unlike [`with_waker_policy`](`Self::with_waker_policy`) can be used for atomics in `static`s.
+    pub fn set_waker_policy(&self, policy: WakerPolicy) {
+        self.policy.store(policy as u8, Ordering::Relaxed);
+    }
+
+    pub fn waker_policy(&self) -> WakerPolicy {
+        WakerPolicy::from_u8(self.policy.load(Ordering::Relaxed))
+    }
+
+    /// Register waker to be woken on the next store unless a waker of another task is waiting.
+    ///
+    /// A waiter is considered waiting from the registration until it is woken, or its wait completes or is dropped.
+    /// Streams occupy the atomic between items too.
+    /// With [`WakerPolicy::Replace`] and [`WakerPolicy::WakeDisplaced`], as well as with [`WakerPolicy::DebugPanic`]
+    /// in release builds, the waker is always registered.
+    ///
+    /// The value must be checked after successful registration to avoid missing the store.
+    pub fn try_register(&self, waker: &Waker) -> Result<(), Occupied> {
+        match self.waker_policy() {
+            WakerPolicy::Replace => self.register_unchecked(waker),
+            WakerPolicy::WakeDisplaced => self.register_displacing(waker),
+            WakerPolicy::DebugPanic if !cfg!(debug_assertions) => self.register_unchecked(waker),
+            WakerPolicy::Reject | WakerPolicy::DebugPanic => self.register_exclusive(waker)?,
+        }
+        Ok(())
+    }
+
+    /// Register waker to be woken on the next store according to [`WakerPolicy`].
+    ///
+    /// The value must be checked after registration to avoid missing the store.
+    pub(crate) fn register(&self, waker: &Waker) {
+        if let Err(err) = self.try_register(waker) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Whether the current policy rejects a competing waiter.
+    fn is_exclusive(&self) -> bool {
+        match self.waker_policy() {
+            WakerPolicy::Reject => true,
+            WakerPolicy::DebugPanic => cfg!(debug_assertions),
+            WakerPolicy::Replace | WakerPolicy::WakeDisplaced => false,
+        }
+    }
+
+    /// Stop occupying the atomic by a completed future whose task registered `waker`.
+    ///
+    /// Matters only for policies that reject competing waiters.
+    pub(crate) fn release(&self, waker: &Waker) {
+        if !self.is_exclusive() || !self.armed.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        let generation = self.generation();
+        if let Some(prev) = self.waker.take() {
+            if !prev.will_wake(waker) {
+                // Waker of another task, put it back.
+                self.register_unchecked(&prev);
+                if self.generation() != generation {
+                    prev.wake();
+                }
+            }
+        }
+    }
+
+    /// Stop occupying the atomic by a future or stream that is dropped before completion.
+    ///
+    /// The registered waker can't be told apart from the waker of another task waiting on the atomic,
+    /// so it is woken: the other task re-polls and registers again, while for the dropping task it is a spurious wake.
+    /// Matters only for policies that reject competing waiters.
+    pub(crate) fn release_dropped(&self) {
+        if self.is_exclusive() && self.armed.swap(false, Ordering::AcqRel) {
+            self.waker.wake();
+        }
+    }
+
+    fn register_exclusive(&self, waker: &Waker) -> Result<(), Occupied> {
+        if self.armed.load(Ordering::Acquire) {
+            let generation = self.generation();
+            if let Some(prev) = self.waker.take() {
+                if !prev.will_wake(waker) {
+                    self.waker.register(&prev);
+                    fence(Ordering::SeqCst);
+                    // A store between `take` and `register` couldn't wake the previous waker.
+                    if self.generation() != generation {
+                        prev.wake();
+                    }
+                    return Err(Occupied);
+                }
+            }
+        }
+        self.register_unchecked(waker);
+        Ok(())
+    }
+
+    fn register_displacing(&self, waker: &Waker) {
+        let prev = self.waker.take();
+        self.register_unchecked(waker);
+        if let Some(prev) = prev.filter(|prev| !prev.will_wake(waker)) {
+            prev.wake();
+        }
+    }
+
+    fn register_unchecked(&self, waker: &Waker) {
+        self.waker.register(waker);
+        self.armed.store(true, Ordering::Relaxed);
+        // Pairs with the fence in `notify`: either the subsequent load sees the store or `notify` sees the flag.
+        fence(Ordering::SeqCst);
+    }
+
+    /// Drop registered waker, e.g. when the task that registered it is unwinding.
+    pub(crate) fn unregister(&self) {
+        drop(self.take_waker());
+    }
+
+    /// Take registered waker, so the atomic is not occupied by it anymore.
+    pub(crate) fn take_waker(&self) -> Option<Waker> {
+        self.armed.store(false, Ordering::Relaxed);
+        self.waker.take()
+    }
+
+    /// Increment generation and wake the subscriber.
+    ///
+    /// The waker is woken at most once per registration, so a burst of stores without polling
+    /// in between costs a single wake.
+    pub(crate) fn notify(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+        self.wake();
+    }
+
+    /// Wake the subscriber without modifying the atomic, e.g. to report that it was closed.
+    pub(crate) fn wake(&self) {
+        fence(Ordering::SeqCst);
+        if self.armed.load(Ordering::Relaxed) && self.armed.swap(false, Ordering::Acquire) {
+            self.waker.wake();
         }
     }
 
@@ -36,25 +294,32 @@ impl<T: Atom> AsyncAtomic<T> {
 
     pub fn store(&self, val: T) {
         self.value.store(val, Ordering::Release);
-        self.waker.wake();
+        self.notify();
     }
 
     pub fn swap(&self, val: T) -> T {
         let old = self.value.swap(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
 
     pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
         self.value
             .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
-            .inspect(|_| self.waker.wake())
+            .inspect(|_| self.notify())
+    }
+
+    /// Move state machine from `from` state to `to` state.
+    ///
+    /// Fails with the actual state if it is not `from`.
+    pub fn transition(&self, from: T, to: T) -> Result<(), T> {
+        self.compare_exchange(from, to).map(|_| ())
     }
 
     pub fn fetch_update<F: FnMut(T) -> Option<T>>(&self, f: F) -> Result<T, T> {
         self.value
             .fetch_update(Ordering::AcqRel, Ordering::Acquire, f)
-            .inspect(|_| self.waker.wake())
+            .inspect(|_| self.notify())
     }
 }
 
@@ -64,17 +329,17 @@ where
 {
     pub fn fetch_and(&self, val: T) -> T {
         let old = self.value.fetch_and(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
     pub fn fetch_or(&self, val: T) -> T {
         let old = self.value.fetch_or(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
     pub fn fetch_xor(&self, val: T) -> T {
         let old = self.value.fetch_xor(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
 }
@@ -85,28 +350,58 @@ where
 {
     pub fn fetch_add(&self, val: T) -> T {
         let old = self.value.fetch_add(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
     pub fn fetch_sub(&self, val: T) -> T {
         let old = self.value.fetch_sub(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
     pub fn fetch_max(&self, val: T) -> T {
         let old = self.value.fetch_max(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
     pub fn fetch_min(&self, val: T) -> T {
         let old = self.value.fetch_min(val, Ordering::AcqRel);
-        self.waker.wake();
+        self.notify();
         old
     }
 }
 
+/// Wakes the subscriber, so that [`WeakSubscriber`](`crate::WeakSubscriber`) waits can detect that the atomic is gone.
+impl<T: Atom> Drop for AsyncAtomic<T> {
+    fn drop(&mut self) {
+        self.waker.wake();
+    }
+}
+
 impl<T: Atom> AsRef<AsyncAtomic<T>> for AsyncAtomic<T> {
     fn as_ref(&self) -> &AsyncAtomic<T> {
         self
     }
 }
+
+/// Atomic is serialized as its current value.
+#[cfg(feature = "serde")]
+impl<T: Atom + serde::Serialize> serde::Serialize for AsyncAtomic<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.load().serialize(serializer)
+    }
+}
+
+/// Atomic is deserialized from a value into a new atomic.
+#[cfg(feature = "serde")]
+impl<'de, T: Atom + serde::Deserialize<'de>> serde::Deserialize<'de> for AsyncAtomic<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<T: Atom + defmt::Format> defmt::Format for AsyncAtomic<T> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "AsyncAtomic({})", self.load())
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, Wait};
+
+macro_rules! impl_bits {
+    ($($width:literal: $t:ty),* $(,)?) => {
+        $(
+            /// Per-bit access for flag words.
+            ///
+            /// All methods panic if bit index `n` is out of range.
+            #[cfg(target_has_atomic = $width)]
+            impl AsyncAtomic<$t> {
+                fn bit(n: u32) -> $t {
+                    assert!(n < <$t>::BITS, "bit index {n} is out of range");
+                    1 << n
+                }
+
+                /// Set `n`-th bit and return its previous state.
+                pub fn set_bit(&self, n: u32) -> bool {
+                    let mask = Self::bit(n);
+                    self.fetch_or(mask) & mask != 0
+                }
+
+                /// Clear `n`-th bit and return its previous state.
+                pub fn clear_bit(&self, n: u32) -> bool {
+                    let mask = Self::bit(n);
+                    self.fetch_and(!mask) & mask != 0
+                }
+
+                /// Get state of `n`-th bit.
+                pub fn get_bit(&self, n: u32) -> bool {
+                    self.load() & Self::bit(n) != 0
+                }
+
+                /// Asynchronously wait for `n`-th bit to be set.
+                pub fn wait_bit_set(&self, n: u32) -> Wait<&Self, impl FnMut($t) -> bool> {
+                    let mask = Self::bit(n);
+                    self.wait(move |x| x & mask != 0)
+                }
+
+                /// Asynchronously wait for `n`-th bit to be cleared.
+                pub fn wait_bit_clear(&self, n: u32) -> Wait<&Self, impl FnMut($t) -> bool> {
+                    let mask = Self::bit(n);
+                    self.wait(move |x| x & mask == 0)
+                }
+            }
+        )*
+    };
+}
+
+impl_bits!(
+    "8": u8,
+    "16": u16,
+    "32": u32,
+    "64": u64,
+    "ptr": usize,
+    "8": i8,
+    "16": i16,
+    "32": i32,
+    "64": i64,
+    "ptr": isize,
+);
@@ -0,0 +1,137 @@
+use crate::{AsyncAtomicRef, Changed};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, PoisonError,
+    },
+    task::Wake,
+    thread::{self, Thread},
+};
+
+/// Mechanism that blocks the thread of [`BlockingSubscriber`] until its waker is woken.
+pub trait Parker: Wake + Send + Sync + 'static {
+    /// Create parker for the current thread.
+    fn new() -> Self;
+
+    /// Block until woken. Returns immediately if woken since the previous call.
+    fn park(&self);
+}
+
+/// Parker based on [`Mutex`] and [`Condvar`].
+///
+/// Works regardless of which thread calls [`park`](`Parker::park`).
+#[derive(Default, Debug)]
+pub struct CondvarParker {
+    flag: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl Parker for CondvarParker {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn park(&self) {
+        let mut flag = self.flag.lock().unwrap_or_else(PoisonError::into_inner);
+        while !*flag {
+            flag = self.cond.wait(flag).unwrap_or_else(PoisonError::into_inner);
+        }
+        *flag = false;
+    }
+}
+
+impl Wake for CondvarParker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        *self.flag.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        self.cond.notify_one();
+    }
+}
+
+/// Parker based on [`thread::park`] and [`Thread::unpark`].
+///
+/// It has lower wake-up latency than [`CondvarParker`] because waking doesn't take a lock.
+/// The parker is bound to the thread it was created on, so the subscriber must be iterated on that thread.
+#[derive(Debug)]
+pub struct ThreadParker {
+    thread: Thread,
+    notified: AtomicBool,
+}
+
+impl Parker for ThreadParker {
+    fn new() -> Self {
+        Self {
+            thread: thread::current(),
+            notified: AtomicBool::new(false),
+        }
+    }
+
+    fn park(&self) {
+        assert_eq!(
+            thread::current().id(),
+            self.thread.id(),
+            "parker is used on a different thread"
+        );
+        while !self.notified.swap(false, Ordering::Acquire) {
+            thread::park();
+        }
+    }
+}
+
+impl Wake for ThreadParker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.notified.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+/// Synchronous subscriber that blocks the current thread until the value is changed.
+///
+/// Iterates over changed values like [`changed`](`AsyncAtomicRef::changed`) stream does, the current value is yielded first.
+/// The iterator never ends.
+///
+/// Blocking mechanism is selected by `P`, e.g. `BlockingSubscriber::<_, ThreadParker>::new(&atomic)`.
+///
+/// *It occupies the waker of the atomic while blocked, so it should not be mixed with async subscribers.*
+pub struct BlockingSubscriber<R: AsyncAtomicRef<Item: PartialEq + Clone>, P: Parker = CondvarParker>
+{
+    pub inner: Changed<R>,
+    parker: Arc<P>,
+    waker: Waker,
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>, P: Parker> BlockingSubscriber<R, P> {
+    /// Create subscriber with parker for the current thread.
+    pub fn new(inner: R) -> Self {
+        let parker = Arc::new(P::new());
+        Self {
+            inner: inner.changed(),
+            waker: Waker::from(parker.clone()),
+            parker,
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>, P: Parker> Iterator for BlockingSubscriber<R, P> {
+    type Item = R::Item;
+
+    fn next(&mut self) -> Option<R::Item> {
+        let mut cx = Context::from_waker(&self.waker);
+        loop {
+            match Pin::new(&mut self.inner).poll(&mut cx) {
+                Poll::Ready(value) => break Some(value),
+                Poll::Pending => self.parker.park(),
+            }
+        }
+    }
+}
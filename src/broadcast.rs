@@ -0,0 +1,313 @@
+use crate::{AsyncAtomicMulti, MultiSubscriber};
+use atomig::{Atom, Atomic as BasicAtomic};
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+use futures::stream::{FusedStream, Stream};
+
+/// Marks slot which is being overwritten.
+const WRITING: usize = usize::MAX;
+
+/// Error returned when receiver missed some values.
+///
+/// Contains the number of skipped values.
+/// See [`BroadcastReceiver`] and [`LaggedUpdates`](`crate::LaggedUpdates`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Lagged(pub usize);
+
+impl fmt::Display for Lagged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiver lagged by {} values", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Lagged {}
+
+struct Slot<T: Atom> {
+    seq: AtomicUsize,
+    value: BasicAtomic<T>,
+}
+
+/// Bounded single-producer broadcast that doesn't allocate.
+///
+/// For primitive types it can be placed in `static` using `const_new`.
+///
+/// Keeps the last `CAP` sent values in a ring buffer, so each of up to `SUBS` receivers sees every value
+/// as long as it doesn't fall behind by more than `CAP` values.
+/// The sender never waits: when the buffer overflows the oldest values are overwritten
+/// and lagging receivers get [`Lagged`] error with the number of missed values.
+///
+/// It also serves as a history of the last `CAP` values for a subscriber that polls infrequently:
+/// [`latest`](`Self::latest`) gives the last sent value like [`AsyncAtomic::load`](`crate::AsyncAtomic::load`) does,
+/// while [`drain`](`BroadcastReceiver::drain`) returns all intermediate values received since the previous drain.
+pub struct Broadcast<T: Atom, const CAP: usize, const SUBS: usize> {
+    head: AsyncAtomicMulti<usize, SUBS>,
+    slots: [Slot<T>; CAP],
+    sender: AtomicBool,
+}
+
+impl<T: Atom + Default, const CAP: usize, const SUBS: usize> Broadcast<T, CAP, SUBS> {
+    pub fn new() -> Self {
+        assert!(CAP > 0, "capacity must be non-zero");
+        Self {
+            head: AsyncAtomicMulti::new(0),
+            slots: core::array::from_fn(|_| Slot {
+                seq: AtomicUsize::new(WRITING),
+                value: BasicAtomic::default(),
+            }),
+            sender: AtomicBool::new(false),
+        }
+    }
+}
+
+macro_rules! impl_const_new {
+    ($($width:literal: $t:ty = $impl:ident, $init:literal;)*) => {
+        $(
+            #[cfg(target_has_atomic = $width)]
+            impl<const CAP: usize, const SUBS: usize> Broadcast<$t, CAP, SUBS> {
+                /// Create broadcast in `const` context, e.g. to initialize a `static`.
+                pub const fn const_new() -> Self {
+                    assert!(CAP > 0, "capacity must be non-zero");
+                    Self {
+                        head: AsyncAtomicMulti::<usize, SUBS>::const_new(0),
+                        slots: [const {
+                            Slot {
+                                seq: AtomicUsize::new(WRITING),
+                                value: BasicAtomic::from_impl(core::sync::atomic::$impl::new($init)),
+                            }
+                        }; CAP],
+                        sender: AtomicBool::new(false),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_const_new! {
+    "8": bool = AtomicBool, false;
+    "8": u8 = AtomicU8, 0;
+    "8": i8 = AtomicI8, 0;
+    "16": u16 = AtomicU16, 0;
+    "16": i16 = AtomicI16, 0;
+    "32": u32 = AtomicU32, 0;
+    "32": i32 = AtomicI32, 0;
+    "64": u64 = AtomicU64, 0;
+    "64": i64 = AtomicI64, 0;
+    "ptr": usize = AtomicUsize, 0;
+    "ptr": isize = AtomicIsize, 0;
+}
+
+impl<T: Atom + Default, const CAP: usize, const SUBS: usize> Default for Broadcast<T, CAP, SUBS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> Broadcast<T, CAP, SUBS> {
+    /// Take the sender. There is only one sender at a time.
+    ///
+    /// Returns `None` if the sender is already taken and not dropped yet.
+    pub fn try_sender(&self) -> Option<BroadcastSender<'_, T, CAP, SUBS>> {
+        self.sender
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| BroadcastSender { owner: self })
+    }
+
+    /// Create receiver that sees values sent after its creation.
+    ///
+    /// Returns `None` if there are already `SUBS` receivers.
+    pub fn try_subscribe(&self) -> Option<BroadcastReceiver<'_, T, CAP, SUBS>> {
+        let head = self.head.try_subscribe()?;
+        let cursor = head.load();
+        Some(BroadcastReceiver {
+            head,
+            slots: &self.slots,
+            cursor,
+        })
+    }
+
+    /// Create receiver that sees values sent after its creation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are already `SUBS` receivers.
+    pub fn subscribe(&self) -> BroadcastReceiver<'_, T, CAP, SUBS> {
+        self.try_subscribe()
+            .expect("all receiver slots are occupied")
+    }
+
+    /// The last sent value or `None` if nothing was sent yet.
+    pub fn latest(&self) -> Option<T> {
+        loop {
+            let head = self.head.load();
+            let seq = head.checked_sub(1)?;
+            let slot = &self.slots[seq % CAP];
+            if slot.seq.load(Ordering::Acquire) == seq {
+                let value = slot.value.load(Ordering::Relaxed);
+                fence(Ordering::Acquire);
+                if slot.seq.load(Ordering::Relaxed) == seq {
+                    break Some(value);
+                }
+            }
+            // Slot was overwritten while reading, retry with the new head.
+        }
+    }
+
+    /// Total number of values sent so far (wrapping on overflow).
+    pub fn sent(&self) -> usize {
+        self.head.load()
+    }
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> fmt::Debug for Broadcast<T, CAP, SUBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Broadcast")
+            .field("sent", &self.sent())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sending side of [`Broadcast`].
+pub struct BroadcastSender<'a, T: Atom, const CAP: usize, const SUBS: usize> {
+    owner: &'a Broadcast<T, CAP, SUBS>,
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> BroadcastSender<'_, T, CAP, SUBS> {
+    /// Send value to all receivers overwriting the oldest one if the buffer is full.
+    ///
+    /// Never waits and doesn't allocate.
+    pub fn send(&self, value: T) {
+        let owner = self.owner;
+        let seq = owner.head.load();
+        let slot = &owner.slots[seq % CAP];
+        slot.seq.store(WRITING, Ordering::Relaxed);
+        fence(Ordering::Release);
+        slot.value.store(value, Ordering::Relaxed);
+        slot.seq.store(seq, Ordering::Release);
+        owner.head.store(seq.wrapping_add(1));
+    }
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> Drop for BroadcastSender<'_, T, CAP, SUBS> {
+    fn drop(&mut self) {
+        self.owner.sender.store(false, Ordering::Release);
+    }
+}
+
+/// Receiving side of [`Broadcast`].
+///
+/// It is also a stream of received values. Stream never terminates.
+pub struct BroadcastReceiver<'a, T: Atom, const CAP: usize, const SUBS: usize> {
+    head: MultiSubscriber<'a, usize, SUBS>,
+    slots: &'a [Slot<T>; CAP],
+    /// Sequence number of the next value to receive.
+    cursor: usize,
+}
+
+impl<'a, T: Atom, const CAP: usize, const SUBS: usize> BroadcastReceiver<'a, T, CAP, SUBS> {
+    /// Receive the next value if there is one.
+    pub fn try_recv(&mut self) -> Result<Option<T>, Lagged> {
+        let head = self.head.load();
+        let behind = head.wrapping_sub(self.cursor);
+        if behind == 0 {
+            return Ok(None);
+        }
+        if behind > CAP {
+            return Err(self.skip_to(head.wrapping_sub(CAP)));
+        }
+
+        let slot = &self.slots[self.cursor % CAP];
+        let mut seq = slot.seq.load(Ordering::Acquire);
+        if seq == self.cursor {
+            let value = slot.value.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            seq = slot.seq.load(Ordering::Relaxed);
+            if seq == self.cursor {
+                self.cursor = self.cursor.wrapping_add(1);
+                return Ok(Some(value));
+            }
+        }
+        // Slot was overwritten by a value sent after `head` was loaded.
+        // Its sequence number tells how far the sender went, so the cursor only moves forward.
+        let cursor = if seq == WRITING {
+            self.cursor.wrapping_add(1)
+        } else {
+            seq.wrapping_add(1).wrapping_sub(CAP)
+        };
+        Err(self.skip_to(cursor))
+    }
+
+    /// Iterate over all values available without waiting.
+    ///
+    /// If some values were overwritten before being received
+    /// then [`Lagged`] is yielded first and the iteration continues from the oldest available value.
+    pub fn drain(&mut self) -> Drain<'_, 'a, T, CAP, SUBS> {
+        Drain { inner: self }
+    }
+
+    fn skip_to(&mut self, cursor: usize) -> Lagged {
+        let skipped = cursor.wrapping_sub(self.cursor);
+        self.cursor = cursor;
+        Lagged(skipped)
+    }
+
+    /// Poll for the next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, Lagged>> {
+        let cursor = self.cursor;
+        if self.head.poll_wait(cx, |head| head != cursor).is_pending() {
+            return Poll::Pending;
+        }
+        match self.try_recv() {
+            Ok(Some(value)) => Poll::Ready(Ok(value)),
+            Ok(None) => Poll::Pending,
+            Err(lagged) => Poll::Ready(Err(lagged)),
+        }
+    }
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> Unpin for BroadcastReceiver<'_, T, CAP, SUBS> {}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> Future for BroadcastReceiver<'_, T, CAP, SUBS> {
+    type Output = Result<T, Lagged>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_recv(cx)
+    }
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> Stream for BroadcastReceiver<'_, T, CAP, SUBS> {
+    type Item = Result<T, Lagged>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> FusedStream
+    for BroadcastReceiver<'_, T, CAP, SUBS>
+{
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Iterator over values available in [`BroadcastReceiver`].
+pub struct Drain<'r, 'a, T: Atom, const CAP: usize, const SUBS: usize> {
+    inner: &'r mut BroadcastReceiver<'a, T, CAP, SUBS>,
+}
+
+impl<T: Atom, const CAP: usize, const SUBS: usize> Iterator for Drain<'_, '_, T, CAP, SUBS> {
+    type Item = Result<T, Lagged>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.try_recv().transpose()
+    }
+}
@@ -0,0 +1,56 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::stream::{FusedStream, Stream};
+
+/// Stream adapter that yields to the executor after `limit` consecutive ready items.
+///
+/// Under a storm of updates a stream of atomic changes is always ready,
+/// so a task consuming it in a loop never returns to the executor and starves other tasks.
+/// This adapter returns `Pending` (waking itself immediately) once the budget is exhausted
+/// and resets the budget every time the inner stream is pending.
+pub struct Budget<S: Stream + Unpin> {
+    pub inner: S,
+    pub limit: usize,
+    pub used: usize,
+}
+
+impl<S: Stream + Unpin> Budget<S> {
+    /// Wrap stream allowing `limit` consecutive ready items before yielding.
+    pub fn new(inner: S, limit: usize) -> Self {
+        assert!(limit > 0, "budget must be non-zero");
+        Self {
+            inner,
+            limit,
+            used: 0,
+        }
+    }
+}
+
+impl<S: Stream + Unpin> Unpin for Budget<S> {}
+
+impl<S: Stream + Unpin> Stream for Budget<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        if self.used >= self.limit {
+            self.used = 0;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let poll = Pin::new(&mut self.inner).poll_next(cx);
+        if poll.is_ready() {
+            self.used += 1;
+        } else {
+            self.used = 0;
+        }
+        poll
+    }
+}
+
+impl<S: FusedStream + Unpin> FusedStream for Budget<S> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
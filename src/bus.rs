@@ -0,0 +1,69 @@
+//! Application-wide registry of named async atomics.
+//!
+//! Allows far-flung modules to publish and subscribe to the same signal by name
+//! without passing handles through every constructor.
+//!
+//! ```
+//! use async_atomic::bus;
+//!
+//! // Somewhere in a battery driver.
+//! bus::get::<u32>("battery_mv").store(3700);
+//!
+//! // Somewhere in UI.
+//! assert_eq!(bus::get::<u32>("battery_mv").load(), 3700);
+//! ```
+
+use crate::AsyncAtomic;
+use atomig::Atom;
+use core::any::{type_name, Any};
+use std::{
+    collections::BTreeMap,
+    string::String,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+type Signal = Arc<dyn Any + Send + Sync>;
+
+static BUS: Mutex<BTreeMap<String, Signal>> = Mutex::new(BTreeMap::new());
+
+/// Get atomic named `name`, creating it with default value on first use.
+///
+/// # Panics
+///
+/// Panics if the atomic named `name` already exists with another type.
+pub fn get<T: Atom + Default + 'static>(name: &str) -> Arc<AsyncAtomic<T>>
+where
+    AsyncAtomic<T>: Send + Sync,
+{
+    let mut bus = BUS.lock().unwrap_or_else(PoisonError::into_inner);
+    let signal = bus
+        .entry(String::from(name))
+        .or_insert_with(|| Arc::new(AsyncAtomic::<T>::default()))
+        .clone();
+    drop(bus);
+    signal.downcast().unwrap_or_else(|_| {
+        panic!(
+            "signal {:?} already exists with a type other than {}",
+            name,
+            type_name::<T>()
+        )
+    })
+}
+
+/// Check whether the atomic named `name` exists.
+pub fn contains(name: &str) -> bool {
+    BUS.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains_key(name)
+}
+
+/// Remove the atomic named `name` from the bus.
+///
+/// Existing handles stay valid, but subsequent [`get`] creates a new atomic.
+/// Returns `true` if the atomic existed.
+pub fn remove(name: &str) -> bool {
+    BUS.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(name)
+        .is_some()
+}
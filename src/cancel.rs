@@ -0,0 +1,88 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, WaitState};
+use core::{
+    fmt,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use pin_project_lite::pin_project;
+
+/// Lightweight cancellation token.
+///
+/// Can be passed to [`wait_with_cancel`](`AsyncAtomicRef::wait_with_cancel`) by reference.
+///
+/// *The token is an async atomic itself, so it wakes only the last polled task awaiting it.*
+/// *To cancel several tasks at once use a token per task or a runtime-provided token.*
+#[derive(Default, Debug)]
+pub struct CancelToken {
+    cancelled: AsyncAtomic<bool>,
+}
+
+impl CancelToken {
+    /// Create token that is not cancelled.
+    pub const fn new() -> Self {
+        Self {
+            cancelled: AsyncAtomic::<bool>::const_new(false),
+        }
+    }
+
+    /// Cancel the token waking the waiting task. Cancellation cannot be undone.
+    pub fn cancel(&self) {
+        self.cancelled.store(true);
+    }
+
+    /// Check whether the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load()
+    }
+
+    /// Asynchronously wait for the token to be cancelled.
+    pub fn cancelled(&self) -> WaitState<&AsyncAtomic<bool>> {
+        self.cancelled.wait_state(true)
+    }
+}
+
+impl<'a> IntoFuture for &'a CancelToken {
+    type Output = ();
+    type IntoFuture = WaitState<&'a AsyncAtomic<bool>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.cancelled()
+    }
+}
+
+/// Error returned when a future was cancelled.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("operation was cancelled")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Cancelled {}
+
+pin_project! {
+    /// Future that resolves with `Err(Cancelled)` if `cancel` future completed before the inner one.
+    pub struct Cancellable<F, C> {
+        #[pin]
+        pub future: F,
+        #[pin]
+        pub cancel: C,
+    }
+}
+
+impl<F: Future, C: Future<Output = ()>> Future for Cancellable<F, C> {
+    type Output = Result<F::Output, Cancelled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Poll::Ready(x) = this.future.poll(cx) {
+            return Poll::Ready(Ok(x));
+        }
+        this.cancel.poll(cx).map(|()| Err(Cancelled))
+    }
+}
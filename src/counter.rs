@@ -0,0 +1,19 @@
+/// Integer that can be used as a monotonic wrapping counter.
+pub trait Counter: Copy + Eq {
+    /// Amount the counter advanced from `prev` to `self` (wrapping on overflow).
+    fn delta(self, prev: Self) -> Self;
+}
+
+macro_rules! impl_counter {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Counter for $t {
+                fn delta(self, prev: Self) -> Self {
+                    self.wrapping_sub(prev)
+                }
+            }
+        )*
+    };
+}
+
+impl_counter!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
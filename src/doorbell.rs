@@ -0,0 +1,45 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, WaitAndSwap};
+
+/// Counting signal for waking a single task, e.g. a driver bottom half from an interrupt handler.
+///
+/// [`ring`](`Self::ring`) doesn't allocate, doesn't lock and never blocks:
+/// it is a few lock-free atomic operations followed by waking the registered waker.
+/// So it is safe to call from an interrupt handler as long as the executor's waker is interrupt-safe too
+/// (which is the case for common embedded executors).
+///
+/// Rings are accumulated until the task consumes them with [`wait`](`Self::wait`) or [`take`](`Self::take`).
+#[derive(Default, Debug)]
+pub struct Doorbell {
+    rings: AsyncAtomic<usize>,
+}
+
+impl Doorbell {
+    /// Create doorbell without pending rings.
+    pub const fn new() -> Self {
+        Self {
+            rings: AsyncAtomic::<usize>::const_new(0),
+        }
+    }
+
+    /// Ring the doorbell waking the waiting task.
+    pub fn ring(&self) {
+        self.rings.fetch_add(1);
+    }
+
+    /// Number of pending rings.
+    pub fn pending(&self) -> usize {
+        self.rings.load()
+    }
+
+    /// Consume pending rings without waiting and return their number.
+    pub fn take(&self) -> usize {
+        self.rings.swap(0)
+    }
+
+    /// Asynchronously wait for at least one ring and consume all pending rings.
+    ///
+    /// Resolves with the number of consumed rings.
+    pub fn wait(&self) -> WaitAndSwap<&AsyncAtomic<usize>, fn(usize) -> bool> {
+        self.rings.wait_and_take()
+    }
+}
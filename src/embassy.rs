@@ -0,0 +1,16 @@
+use crate::Timer;
+use core::time::Duration;
+
+/// [`Timer`] backed by `embassy-time`, for time-based features on embedded executors.
+///
+/// Durations longer than `embassy-time` can represent are clamped to [`embassy_time::Duration::MAX`].
+#[derive(Clone, Copy, Default, Debug)]
+pub struct EmbassyTimer;
+
+impl Timer for EmbassyTimer {
+    type Sleep = embassy_time::Timer;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        embassy_time::Timer::after(duration.try_into().unwrap_or(embassy_time::Duration::MAX))
+    }
+}
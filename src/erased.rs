@@ -0,0 +1,138 @@
+use crate::{AsyncAtomic, AsyncAtomicRef};
+use atomig::Atom;
+use core::{
+    any,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Primitive representation that can be converted to and from raw `u64` bits.
+///
+/// Signed integers are sign-extended, so that raw value of `-1i8` is `u64::MAX`.
+pub trait RawBits: Sized {
+    fn to_bits(self) -> u64;
+    /// Truncate raw bits to the representation.
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! impl_raw_bits {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl RawBits for $ty {
+                fn to_bits(self) -> u64 {
+                    self as u64
+                }
+                fn from_bits(bits: u64) -> Self {
+                    bits as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_bits!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl RawBits for bool {
+    fn to_bits(self) -> u64 {
+        self as u64
+    }
+    fn from_bits(bits: u64) -> Self {
+        bits != 0
+    }
+}
+
+/// Type-erased async atomic that operates on raw `u64` bits of the value.
+///
+/// Object-safe, so atomics of different types can be stored in a single collection,
+/// e.g. to list all signals in a debug console.
+///
+/// ```
+/// use async_atomic::{AsyncAtomic, DynAsyncAtomic};
+///
+/// let enabled = AsyncAtomic::new(true);
+/// let level = AsyncAtomic::<i16>::new(-3);
+/// let signals: [(&str, &dyn DynAsyncAtomic); 2] = [("enabled", &enabled), ("level", &level)];
+///
+/// signals[1].1.store_raw(5);
+/// assert_eq!(level.load(), 5);
+/// assert_eq!(signals[0].1.load_raw(), 1);
+/// ```
+pub trait DynAsyncAtomic: Sync {
+    /// Name of the value type.
+    fn type_name(&self) -> &'static str;
+
+    /// Load raw bits of the value.
+    fn load_raw(&self) -> u64;
+
+    /// Store value from raw bits.
+    ///
+    /// Bits that don't fit into the representation are truncated.
+    /// Bits that don't represent a valid value are handled by [`Atom::unpack`] of the type.
+    fn store_raw(&self, bits: u64);
+
+    /// Number of modifications made to the atomic so far (wrapping on overflow).
+    fn generation(&self) -> usize;
+
+    /// Poll for predicate on raw bits to be `true`.
+    fn poll_wait_raw(&self, cx: &mut Context<'_>, pred: &mut dyn FnMut(u64) -> bool) -> Poll<()>;
+
+    /// Stop occupying the atomic by a wait dropped before completion, see [`WakerPolicy`](`crate::WakerPolicy`).
+    fn release_raw(&self) {}
+}
+
+impl<T: Atom<Repr: RawBits>> DynAsyncAtomic for AsyncAtomic<T> {
+    fn type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+
+    fn load_raw(&self) -> u64 {
+        self.load().pack().to_bits()
+    }
+
+    fn store_raw(&self, bits: u64) {
+        self.store(T::unpack(T::Repr::from_bits(bits)));
+    }
+
+    fn generation(&self) -> usize {
+        AsyncAtomic::generation(self)
+    }
+
+    fn poll_wait_raw(&self, cx: &mut Context<'_>, pred: &mut dyn FnMut(u64) -> bool) -> Poll<()> {
+        self.poll_wait(cx, |value: T| pred(value.pack().to_bits()))
+    }
+
+    fn release_raw(&self) {
+        self.release_dropped();
+    }
+}
+
+impl dyn DynAsyncAtomic + '_ {
+    /// Asynchronously wait for predicate on raw bits to be `true`.
+    pub fn wait_raw<F: FnMut(u64) -> bool>(&self, pred: F) -> DynWait<'_, F> {
+        DynWait { inner: self, pred }
+    }
+}
+
+/// Future to wait for predicate on [`DynAsyncAtomic`].
+pub struct DynWait<'a, F: FnMut(u64) -> bool> {
+    pub inner: &'a dyn DynAsyncAtomic,
+    pub pred: F,
+}
+
+impl<F: FnMut(u64) -> bool> Unpin for DynWait<'_, F> {}
+
+impl<F: FnMut(u64) -> bool> Drop for DynWait<'_, F> {
+    fn drop(&mut self) {
+        self.inner.release_raw();
+    }
+}
+
+impl<F: FnMut(u64) -> bool> Future for DynWait<'_, F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.inner.poll_wait_raw(cx, &mut this.pred)
+    }
+}
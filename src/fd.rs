@@ -0,0 +1,129 @@
+use crate::AsyncAtomicRef;
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    ops::Deref,
+    os::unix::{
+        io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+        net::UnixStream,
+    },
+    sync::Arc,
+    task::{Wake, Waker},
+};
+
+/// Waker that writes a byte into the socket.
+struct Signal(UnixStream);
+
+impl Wake for Signal {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        // Socket is non-blocking, if it is full then the reader is already notified.
+        let _ = (&self.0).write(&[1]);
+    }
+}
+
+/// Subscriber that mirrors atomic notifications into a file descriptor.
+///
+/// The descriptor becomes readable when the atomic is updated,
+/// so non-async event loops can wait for it using `poll(2)`, `select(2)`, etc.
+///
+/// Like other subscribers it occupies the waker of the atomic,
+/// so there should be no other subscriptions while it is in use.
+///
+/// After the descriptor became readable [`reset`](`Self::reset`) should be called to re-arm the notifier,
+/// and only after that the value should be loaded, so that no update is missed.
+///
+/// The notifier implements [`AsRawFd`], so it can be passed to `tokio::io::unix::AsyncFd` directly.
+/// With `mio` feature enabled it also implements `mio::event::Source` and can be registered in `mio::Poll`.
+pub struct FdNotifier<R: AsyncAtomicRef> {
+    /// Always `Some` except in [`into_inner`](`Self::into_inner`).
+    inner: Option<R>,
+    reader: UnixStream,
+    waker: Waker,
+}
+
+impl<R: AsyncAtomicRef> FdNotifier<R> {
+    pub fn new(inner: R) -> io::Result<Self> {
+        let (reader, writer) = UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        writer.set_nonblocking(true)?;
+        let this = Self {
+            inner: Some(inner),
+            reader,
+            waker: Waker::from(Arc::new(Signal(writer))),
+        };
+        this.reset()?;
+        Ok(this)
+    }
+
+    /// Drain pending notifications and register for the next one.
+    pub fn reset(&self) -> io::Result<()> {
+        let mut buf = [0; 64];
+        loop {
+            match (&self.reader).read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        self.as_atomic().register(&self.waker);
+        Ok(())
+    }
+
+    pub fn into_inner(mut self) -> R {
+        self.as_atomic().release_dropped();
+        self.inner.take().unwrap()
+    }
+}
+
+impl<R: AsyncAtomicRef> Deref for FdNotifier<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref().unwrap()
+    }
+}
+
+impl<R: AsyncAtomicRef> Drop for FdNotifier<R> {
+    fn drop(&mut self) {
+        if let Some(inner) = &self.inner {
+            inner.as_atomic().release_dropped();
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef> AsFd for FdNotifier<R> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.reader.as_fd()
+    }
+}
+
+impl<R: AsyncAtomicRef> AsRawFd for FdNotifier<R> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.reader.as_raw_fd()
+    }
+}
+
+#[cfg(feature = "mio")]
+impl<R: AsyncAtomicRef> mio::event::Source for FdNotifier<R> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        mio::unix::SourceFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        mio::unix::SourceFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        mio::unix::SourceFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
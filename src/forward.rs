@@ -0,0 +1,57 @@
+use crate::AsyncAtomicRef;
+use core::{
+    future::{poll_fn, Future, IntoFuture},
+    pin::{pin, Pin},
+    task::Poll,
+};
+
+/// Call async `f` for the current value and then for every change of the atomic until `cancel` resolves.
+///
+/// Changes made while `f` is running are coalesced, so `f` receives the latest value after it completes.
+/// Cancellation is checked only between calls, a call in progress is always completed.
+/// Dropping the future stops forwarding immediately.
+///
+/// *The future occupies the waker of the atomic.*
+pub async fn forward_changes<R, F, Fut, C>(atomic: R, mut f: F, cancel: C)
+where
+    R: AsyncAtomicRef<Item: PartialEq + Clone>,
+    F: FnMut(R::Item) -> Fut,
+    Fut: Future<Output = ()>,
+    C: IntoFuture<Output = ()>,
+{
+    let mut changes = atomic.changed();
+    let mut cancel = pin!(cancel.into_future());
+    loop {
+        let value = poll_fn(|cx| {
+            if cancel.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+            Pin::new(&mut changes).poll(cx).map(Some)
+        })
+        .await;
+        match value {
+            Some(value) => f(value).await,
+            None => break,
+        }
+    }
+}
+
+/// Call plain callback `f` for the current value and then for every change of the atomic until `cancel` resolves.
+///
+/// Same as [`forward_changes`] but for synchronous callbacks.
+pub async fn for_each_change<R, F, C>(atomic: R, mut f: F, cancel: C)
+where
+    R: AsyncAtomicRef<Item: PartialEq + Clone>,
+    F: FnMut(R::Item),
+    C: IntoFuture<Output = ()>,
+{
+    forward_changes(
+        atomic,
+        move |value| {
+            f(value);
+            async {}
+        },
+        cancel,
+    )
+    .await
+}
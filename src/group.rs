@@ -0,0 +1,103 @@
+use crate::{AsyncAtomic, AsyncAtomicRef};
+use atomig::Atom;
+
+/// Node of a signal tree that is notified whenever any of its members or subgroups change.
+///
+/// The group is an async atomic counter of changes itself,
+/// so subscribing to it (e.g. with [`updates`](`AsyncAtomicRef::updates`)) means watching "anything in this subsystem".
+/// Groups are linked by references, so a tree can be built from `static`s without allocation.
+#[derive(Default, Debug)]
+pub struct SignalGroup<'a> {
+    changes: AsyncAtomic<usize>,
+    parent: Option<&'a SignalGroup<'a>>,
+}
+
+impl<'a> SignalGroup<'a> {
+    /// Create root group.
+    pub const fn new() -> Self {
+        Self {
+            changes: AsyncAtomic::<usize>::const_new(0),
+            parent: None,
+        }
+    }
+
+    /// Create subgroup of `parent`.
+    pub const fn with_parent(parent: &'a SignalGroup<'a>) -> Self {
+        Self {
+            changes: AsyncAtomic::<usize>::const_new(0),
+            parent: Some(parent),
+        }
+    }
+
+    /// Notify this group and all its ancestors about a change.
+    pub fn touch(&self) {
+        let mut group = Some(self);
+        while let Some(this) = group {
+            this.changes.fetch_add(1);
+            group = this.parent;
+        }
+    }
+}
+
+impl AsyncAtomicRef for SignalGroup<'_> {
+    type Item = usize;
+    fn as_atomic(&self) -> &AsyncAtomic<usize> {
+        &self.changes
+    }
+}
+
+/// Async atomic that belongs to a [`SignalGroup`].
+///
+/// Stores made through the member's methods also notify the group and its ancestors.
+/// *Stores made directly through [`as_atomic`](`AsyncAtomicRef::as_atomic`) don't propagate to the group.*
+#[derive(Debug)]
+pub struct GroupMember<'a, T: Atom> {
+    atomic: AsyncAtomic<T>,
+    group: &'a SignalGroup<'a>,
+}
+
+impl<'a, T: Atom> GroupMember<'a, T> {
+    pub fn new(value: T, group: &'a SignalGroup<'a>) -> Self {
+        Self {
+            atomic: AsyncAtomic::new(value),
+            group,
+        }
+    }
+
+    /// Group the member belongs to.
+    pub fn group(&self) -> &'a SignalGroup<'a> {
+        self.group
+    }
+
+    pub fn load(&self) -> T {
+        self.atomic.load()
+    }
+
+    pub fn store(&self, val: T) {
+        self.atomic.store(val);
+        self.group.touch();
+    }
+
+    pub fn swap(&self, val: T) -> T {
+        let old = self.atomic.swap(val);
+        self.group.touch();
+        old
+    }
+
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
+        self.atomic
+            .compare_exchange(current, new)
+            .inspect(|_| self.group.touch())
+    }
+
+    pub fn fetch_update<F: FnMut(T) -> Option<T>>(&self, f: F) -> Result<T, T> {
+        self.atomic.fetch_update(f).inspect(|_| self.group.touch())
+    }
+}
+
+impl<T: Atom> AsyncAtomicRef for GroupMember<'_, T> {
+    type Item = T;
+    fn as_atomic(&self) -> &AsyncAtomic<T> {
+        &self.atomic
+    }
+}
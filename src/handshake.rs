@@ -0,0 +1,50 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, Updates};
+use atomig::Atom;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Request/response handshake between a single client and a single server.
+///
+/// Consists of two atomics, one per direction, so that each of them is awaited by a single side only.
+/// Requests and responses are detected by [`generation`](`AsyncAtomic::generation`),
+/// so it is fine to send the same value multiple times.
+///
+/// *Only one [`call`](`Self::call`) and one [`serve`](`Self::serve`) may be in progress at a time.*
+#[derive(Default, Debug)]
+pub struct Handshake<Req: Atom, Resp: Atom> {
+    request: AsyncAtomic<Req>,
+    response: AsyncAtomic<Resp>,
+    /// Generation of the last served request.
+    served: AtomicUsize,
+}
+
+impl<Req: Atom, Resp: Atom> Handshake<Req, Resp> {
+    /// Create handshake with initial values of request and response.
+    ///
+    /// Initial values are never delivered to the other side.
+    pub fn new(request: Req, response: Resp) -> Self {
+        Self {
+            request: AsyncAtomic::new(request),
+            response: AsyncAtomic::new(response),
+            served: AtomicUsize::new(0),
+        }
+    }
+
+    /// Send request and wait for the response.
+    pub async fn call(&self, request: Req) -> Resp {
+        // Subscribe before sending request to not miss the response.
+        let response = (&self.response).updates();
+        self.request.store(request);
+        response.await
+    }
+
+    /// Wait for the request and respond to it with the value returned by `handler`.
+    pub async fn serve<F: FnOnce(Req) -> Resp>(&self, handler: F) {
+        let mut requests = Updates {
+            inner: &self.request,
+            seen: self.served.load(Ordering::Relaxed),
+        };
+        let request = (&mut requests).await;
+        self.served.store(requests.seen, Ordering::Relaxed);
+        self.response.store(handler(request));
+    }
+}
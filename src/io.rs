@@ -0,0 +1,92 @@
+use crate::AsyncAtomicRef;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::io::{AsyncRead, AsyncWrite};
+use pin_project_lite::pin_project;
+use std::io;
+
+pin_project! {
+    /// Reader that adds number of bytes read to the counter atomic.
+    ///
+    /// Progress can be observed with the counter wait/stream API without touching the reading code.
+    pub struct CountingReader<R, C> {
+        #[pin]
+        pub inner: R,
+        pub counter: C,
+    }
+}
+
+impl<R, C: AsyncAtomicRef<Item = u64>> CountingReader<R, C> {
+    pub fn new(inner: R, counter: C) -> Self {
+        Self { inner, counter }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead, C: AsyncAtomicRef<Item = u64>> AsyncRead for CountingReader<R, C> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.inner.poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            if n != 0 {
+                this.counter.as_atomic().fetch_add(n as u64);
+            }
+        }
+        poll
+    }
+}
+
+pin_project! {
+    /// Writer that adds number of bytes written to the counter atomic.
+    ///
+    /// Progress can be observed with the counter wait/stream API without touching the writing code.
+    pub struct CountingWriter<W, C> {
+        #[pin]
+        pub inner: W,
+        pub counter: C,
+    }
+}
+
+impl<W, C: AsyncAtomicRef<Item = u64>> CountingWriter<W, C> {
+    pub fn new(inner: W, counter: C) -> Self {
+        Self { inner, counter }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: AsyncWrite, C: AsyncAtomicRef<Item = u64>> AsyncWrite for CountingWriter<W, C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.inner.poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            if n != 0 {
+                this.counter.as_atomic().fetch_add(n as u64);
+            }
+        }
+        poll
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
@@ -0,0 +1,137 @@
+use core::{
+    array,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+};
+use futures::task::AtomicWaker;
+
+/// Several small fields (lanes) packed into a single 64-bit atomic.
+///
+/// There are `N` lanes of `64 / N` bits each. Every lane has its own waker,
+/// so modification of a lane notifies only the subscriber of that lane.
+///
+/// Values wider than a lane are truncated, lane arithmetic wraps around without affecting other lanes.
+///
+/// Available only on targets that support 64-bit atomics.
+///
+/// *There is a single waker per lane, so there should be only single subscription to each lane at a time.*
+#[derive(Debug)]
+pub struct Lanes<const N: usize> {
+    value: AtomicU64,
+    wakers: [AtomicWaker; N],
+}
+
+impl<const N: usize> Lanes<N> {
+    /// Width of a single lane in bits.
+    pub const BITS: u32 = {
+        assert!(N > 0 && 64 % N == 0, "64 bits cannot be split into N lanes");
+        (64 / N) as u32
+    };
+    const MASK: u64 = u64::MAX >> (64 - Self::BITS);
+
+    pub fn new(values: [u64; N]) -> Self {
+        Self {
+            value: AtomicU64::new(Self::pack(values)),
+            wakers: array::from_fn(|_| AtomicWaker::new()),
+        }
+    }
+
+    fn pack(values: [u64; N]) -> u64 {
+        values
+            .into_iter()
+            .enumerate()
+            .fold(0, |x, (i, v)| Self::insert(x, i, v))
+    }
+    fn extract(x: u64, i: usize) -> u64 {
+        assert!(i < N, "lane index {i} is out of range");
+        (x >> (i as u32 * Self::BITS)) & Self::MASK
+    }
+    fn insert(x: u64, i: usize, v: u64) -> u64 {
+        assert!(i < N, "lane index {i} is out of range");
+        let shift = i as u32 * Self::BITS;
+        (x & !(Self::MASK << shift)) | ((v & Self::MASK) << shift)
+    }
+
+    /// Replace value of `i`-th lane with `f(value)`, wake its subscriber and return previous value.
+    fn modify<F: Fn(u64) -> u64>(&self, i: usize, f: F) -> u64 {
+        let old = self
+            .value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |x| {
+                Some(Self::insert(x, i, f(Self::extract(x, i))))
+            })
+            .unwrap();
+        self.wakers[i].wake();
+        Self::extract(old, i)
+    }
+
+    /// Load value of `i`-th lane.
+    pub fn load(&self, i: usize) -> u64 {
+        Self::extract(self.value.load(Ordering::Acquire), i)
+    }
+    /// Load values of all lanes at once.
+    pub fn load_all(&self) -> [u64; N] {
+        let x = self.value.load(Ordering::Acquire);
+        array::from_fn(|i| Self::extract(x, i))
+    }
+
+    pub fn store(&self, i: usize, val: u64) {
+        self.modify(i, |_| val);
+    }
+    pub fn swap(&self, i: usize, val: u64) -> u64 {
+        self.modify(i, |_| val)
+    }
+    pub fn fetch_add(&self, i: usize, val: u64) -> u64 {
+        self.modify(i, |x| x.wrapping_add(val))
+    }
+    pub fn fetch_sub(&self, i: usize, val: u64) -> u64 {
+        self.modify(i, |x| x.wrapping_sub(val))
+    }
+
+    /// Asynchronously wait for predicate on `i`-th lane to be `true`.
+    pub fn wait<F: FnMut(u64) -> bool>(&self, i: usize, pred: F) -> LaneWait<'_, N, F> {
+        assert!(i < N, "lane index {i} is out of range");
+        LaneWait {
+            lanes: self,
+            index: i,
+            pred,
+        }
+    }
+}
+
+impl<const N: usize> Default for Lanes<N> {
+    fn default() -> Self {
+        Self::new([0; N])
+    }
+}
+
+/// Future to wait for specific value of a lane.
+pub struct LaneWait<'a, const N: usize, F: FnMut(u64) -> bool> {
+    pub lanes: &'a Lanes<N>,
+    pub index: usize,
+    pub pred: F,
+}
+
+impl<const N: usize, F: FnMut(u64) -> bool> Unpin for LaneWait<'_, N, F> {}
+
+impl<const N: usize, F: FnMut(u64) -> bool> Future for LaneWait<'_, N, F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (lanes, index) = (self.lanes, self.index);
+        lanes.wakers[index].register(cx.waker());
+        if (self.pred)(lanes.load(index)) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for Lanes<N> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "Lanes({})", self.load_all())
+    }
+}
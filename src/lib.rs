@@ -7,16 +7,155 @@
 //!
 //! *Note that if there are more than one future at the same time then only the most recently `poll`ed future will be notified.*
 //! *Older futures will never receive an update, so it's up to user to ensure that only one of them `.await`ing at a time.*
+//!
+//! The crate works on targets without threads support, such as `wasm32-unknown-unknown`,
+//! where atomic operations are lowered to plain memory accesses.
+//! For single-threaded executors there is also [`LocalAsyncAtomic`] that doesn't use atomics at all.
+//!
+//! With `derive` feature [`Atom`] can also be derived for fieldless enums and single-field structs.
+//! The generated code refers to `atomig` crate, so crates that don't depend on it directly
+//! should bring the re-exported one into scope:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use async_atomic::{atomig, Atom, AsyncAtomic};
+//!
+//! #[derive(Atom, Clone, Copy, PartialEq, Debug)]
+//! #[repr(u8)]
+//! enum State {
+//!     Idle,
+//!     Busy,
+//! }
+//!
+//! let state = AsyncAtomic::new(State::Idle);
+//! assert_eq!(state.compare_exchange(State::Idle, State::Busy), Ok(State::Idle));
+//! assert_eq!(state.load(), State::Busy);
+//! # }
+//! ```
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod array;
 mod async_;
 mod atomic;
+mod bits;
+#[cfg(feature = "std")]
+mod blocking;
+mod broadcast;
+mod budget;
+#[cfg(feature = "std")]
+pub mod bus;
+mod cancel;
+mod counter;
+mod doorbell;
+#[cfg(feature = "embassy-time")]
+mod embassy;
+mod erased;
+#[cfg(all(feature = "std", unix))]
+mod fd;
+mod forward;
+mod group;
+mod handshake;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+mod io;
+#[cfg(target_has_atomic = "64")]
+mod lanes;
+mod local;
+#[cfg(target_has_atomic = "64")]
+mod mailbox;
+mod multi;
+mod newtype;
+#[cfg(target_has_atomic = "64")]
+mod pair;
+mod primitive;
+#[cfg(target_has_atomic = "64")]
+mod progress;
+mod rate;
+mod reactive;
+#[cfg(feature = "alloc")]
+mod registry;
+#[cfg(feature = "futures-signals")]
+mod signal;
+mod snapshot;
+#[cfg(feature = "alloc")]
+mod split;
+#[cfg(target_has_atomic = "64")]
+mod stats;
+#[cfg(feature = "alloc")]
+mod stm;
+mod subscriber;
+mod tagged;
+#[cfg(target_has_atomic = "64")]
+mod time;
+mod timer;
+#[cfg(feature = "tokio")]
+mod watch;
+mod watchdog;
+mod watermark;
 
-pub use atomig::Atom;
+/// Re-exported for the code generated by [`Atom`] derive.
+#[cfg(feature = "derive")]
+pub use atomig;
+pub use atomig::{Atom, AtomInteger, AtomLogic};
 
+pub use array::*;
 pub use async_::*;
 pub use atomic::*;
+#[cfg(feature = "std")]
+pub use blocking::*;
+pub use broadcast::*;
+pub use budget::*;
+pub use cancel::*;
+pub use counter::*;
+pub use doorbell::*;
+#[cfg(feature = "embassy-time")]
+pub use embassy::*;
+pub use erased::*;
+#[cfg(all(feature = "std", unix))]
+pub use fd::*;
+pub use forward::*;
+pub use group::*;
+pub use handshake::*;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+pub use io::*;
+#[cfg(target_has_atomic = "64")]
+pub use lanes::*;
+pub use local::*;
+#[cfg(target_has_atomic = "64")]
+pub use mailbox::*;
+pub use multi::*;
+#[cfg(target_has_atomic = "64")]
+pub use pair::*;
+pub use primitive::*;
+#[cfg(target_has_atomic = "64")]
+pub use progress::*;
+pub use rate::*;
+pub use reactive::*;
+#[cfg(feature = "alloc")]
+pub use registry::*;
+#[cfg(feature = "futures-signals")]
+pub use signal::*;
+pub use snapshot::*;
+#[cfg(feature = "alloc")]
+pub use split::*;
+#[cfg(target_has_atomic = "64")]
+pub use stats::*;
+#[cfg(feature = "alloc")]
+pub use stm::*;
+pub use subscriber::*;
+pub use tagged::*;
+#[cfg(target_has_atomic = "64")]
+pub use time::*;
+pub use timer::*;
+#[cfg(feature = "tokio")]
+pub use watch::*;
+pub use watchdog::*;
+pub use watermark::*;
 
 pub mod prelude {
     pub use crate::AsyncAtomicRef;
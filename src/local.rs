@@ -0,0 +1,186 @@
+use core::{
+    cell::Cell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use futures::stream::{FusedStream, Stream};
+
+/// Single-threaded counterpart of [`AsyncAtomic`](`crate::AsyncAtomic`).
+///
+/// Uses [`Cell`] instead of atomics and stores waker without synchronization,
+/// so it is cheaper but cannot be shared between threads.
+/// Suitable for single-threaded executors (embedded, WASM).
+///
+/// *Like [`AsyncAtomic`](`crate::AsyncAtomic`) there is only a single waker, so there should be only single subscription at a time.*
+#[derive(Default)]
+pub struct LocalAsyncAtomic<T: Copy> {
+    value: Cell<T>,
+    generation: Cell<usize>,
+    waker: Cell<Option<Waker>>,
+}
+
+impl<T: Copy> LocalAsyncAtomic<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: Cell::new(value),
+            generation: Cell::new(0),
+            waker: Cell::new(None),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Number of modifications made to the atomic so far (wrapping on overflow).
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+
+    fn notify(&self) {
+        self.generation.set(self.generation.get().wrapping_add(1));
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn register(&self, waker: &Waker) {
+        match self.waker.take() {
+            Some(old) if old.will_wake(waker) => self.waker.set(Some(old)),
+            _ => self.waker.set(Some(waker.clone())),
+        }
+    }
+
+    pub fn load(&self) -> T {
+        self.value.get()
+    }
+
+    pub fn store(&self, val: T) {
+        self.value.set(val);
+        self.notify();
+    }
+
+    pub fn swap(&self, val: T) -> T {
+        let old = self.value.replace(val);
+        self.notify();
+        old
+    }
+
+    pub fn fetch_update<F: FnOnce(T) -> Option<T>>(&self, f: F) -> Result<T, T> {
+        let old = self.value.get();
+        match f(old) {
+            Some(new) => {
+                self.value.set(new);
+                self.notify();
+                Ok(old)
+            }
+            None => Err(old),
+        }
+    }
+
+    /// Asynchronously wait for predicate to be `true`.
+    pub fn wait<F: FnMut(T) -> bool>(&self, pred: F) -> LocalWait<'_, T, F> {
+        LocalWait { inner: self, pred }
+    }
+
+    /// Asynchronously wait until `map` returned `Some(x)` and then store `x` in atomic.
+    pub fn wait_and_update<F: FnMut(T) -> Option<T>>(
+        &self,
+        map: F,
+    ) -> LocalWaitAndUpdate<'_, T, F> {
+        LocalWaitAndUpdate { inner: self, map }
+    }
+
+    /// Stream that yields when value is changed.
+    ///
+    /// The current value is yielded on the first poll.
+    pub fn changed(&self) -> LocalChanged<'_, T>
+    where
+        T: PartialEq,
+    {
+        LocalChanged {
+            inner: self,
+            prev: None,
+        }
+    }
+}
+
+/// Future to wait for specific value.
+pub struct LocalWait<'a, T: Copy, F: FnMut(T) -> bool> {
+    pub inner: &'a LocalAsyncAtomic<T>,
+    pub pred: F,
+}
+
+impl<T: Copy, F: FnMut(T) -> bool> Unpin for LocalWait<'_, T, F> {}
+
+impl<T: Copy, F: FnMut(T) -> bool> Future for LocalWait<'_, T, F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner;
+        inner.register(cx.waker());
+        if (self.pred)(inner.load()) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Future to wait and update a value.
+pub struct LocalWaitAndUpdate<'a, T: Copy, F: FnMut(T) -> Option<T>> {
+    pub inner: &'a LocalAsyncAtomic<T>,
+    pub map: F,
+}
+
+impl<T: Copy, F: FnMut(T) -> Option<T>> Unpin for LocalWaitAndUpdate<'_, T, F> {}
+
+impl<T: Copy, F: FnMut(T) -> Option<T>> Future for LocalWaitAndUpdate<'_, T, F> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.inner.register(cx.waker());
+        match this.inner.fetch_update(&mut this.map) {
+            Ok(x) => Poll::Ready(x),
+            Err(_) => Poll::Pending,
+        }
+    }
+}
+
+/// Stream that yields value when it change.
+pub struct LocalChanged<'a, T: Copy + PartialEq> {
+    pub inner: &'a LocalAsyncAtomic<T>,
+    pub prev: Option<T>,
+}
+
+impl<T: Copy + PartialEq> Unpin for LocalChanged<'_, T> {}
+
+impl<T: Copy + PartialEq> Future for LocalChanged<'_, T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.register(cx.waker());
+        let value = self.inner.load();
+        if self.prev.replace(value).is_none_or(|prev| prev != value) {
+            Poll::Ready(value)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Stream for LocalChanged<'_, T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<T: Copy + PartialEq> FusedStream for LocalChanged<'_, T> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{AsyncAtomic, AsyncAtomicRef};
+use atomig::Atom;
+use core::marker::PhantomData;
+
+const FRESH: u64 = 1 << 32;
+
+/// Single-producer single-consumer mailbox that holds the latest posted value.
+///
+/// Value is packed together with a *fresh* flag into a single atomic,
+/// so the consumer receives only values it hasn't taken yet.
+/// If the producer posts faster than the consumer takes then older values are overwritten.
+///
+/// Available only on targets that support 64-bit atomics.
+#[derive(Debug)]
+pub struct Mailbox<T: Atom<Repr = u32>> {
+    inner: AsyncAtomic<u64>,
+    _ghost: PhantomData<T>,
+}
+
+impl<T: Atom<Repr = u32>> Mailbox<T> {
+    /// Create mailbox with initial value that is not fresh.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: AsyncAtomic::new(u64::from(value.pack())),
+            _ghost: PhantomData,
+        }
+    }
+
+    /// Post new value overwriting the previous one.
+    pub fn post(&self, value: T) {
+        self.inner.store(FRESH | u64::from(value.pack()));
+    }
+
+    /// Load the last posted value regardless of whether it was taken or not.
+    pub fn load(&self) -> T {
+        T::unpack(self.inner.load() as u32)
+    }
+
+    /// Take value if it is fresh, otherwise return `None`.
+    pub fn try_take(&self) -> Option<T> {
+        self.inner
+            .fetch_update(Self::take_fresh)
+            .ok()
+            .map(|x| T::unpack(x as u32))
+    }
+
+    /// Wait for fresh value and take it.
+    pub async fn take_new(&self) -> T {
+        let x = self.inner.wait_and_update(Self::take_fresh).await;
+        T::unpack(x as u32)
+    }
+
+    fn take_fresh(x: u64) -> Option<u64> {
+        (x & FRESH != 0).then_some(x & !FRESH)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<T: Atom<Repr = u32> + defmt::Format> defmt::Format for Mailbox<T> {
+    fn format(&self, fmt: defmt::Formatter) {
+        let x = self.inner.load();
+        defmt::write!(
+            fmt,
+            "Mailbox {{ value: {}, fresh: {} }}",
+            T::unpack(x as u32),
+            x & FRESH != 0
+        )
+    }
+}
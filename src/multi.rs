@@ -0,0 +1,549 @@
+use atomig::{
+    impls::{PrimitiveAtom, PrimitiveAtomInteger, PrimitiveAtomLogic},
+    Atom, AtomInteger, AtomLogic, Atomic as BasicAtomic,
+};
+use core::{
+    fmt,
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+use futures::{
+    stream::{FusedStream, Stream},
+    task::AtomicWaker,
+};
+
+/// Which subscribers of [`AsyncAtomicMulti`] are woken on store.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NotifyPolicy {
+    /// Wake a single waiting subscriber, like a semaphore does.
+    ///
+    /// The subscriber with the highest priority is picked,
+    /// subscribers with equal priority are picked in round-robin order.
+    One,
+    /// Wake all waiting subscribers, like a watch channel does.
+    #[default]
+    All,
+    /// Wake a single waiting subscriber that has been waiting the longest, so waiters take turns in arrival order.
+    ///
+    /// Subscribers with higher priority are still picked first.
+    /// Re-polling while waiting keeps the place in the queue, while a woken subscriber that waits again goes to the end.
+    Fifo,
+}
+
+/// Atomic value with `N` statically allocated waker slots.
+///
+/// Unlike [`AsyncAtomic`](`crate::AsyncAtomic`) up to `N` tasks can wait concurrently on the same atomic,
+/// each one through its own [`MultiSubscriber`].
+/// By default every store wakes all subscribers, see [`NotifyPolicy`].
+/// No heap allocation is required, so for primitive types it can be placed in `static` on `no_std` targets
+/// using `const_new`.
+pub struct AsyncAtomicMulti<T: Atom, const N: usize> {
+    value: BasicAtomic<T>,
+    generation: AtomicUsize,
+    wakers: [AtomicWaker; N],
+    taken: [AtomicBool; N],
+    /// Whether the waker in the slot was registered since the last wake.
+    armed: [AtomicBool; N],
+    /// Priorities of subscribers in the slots.
+    priorities: [AtomicUsize; N],
+    /// Arrival numbers of subscribers waiting in the slots, used by [`NotifyPolicy::Fifo`].
+    arrivals: [AtomicUsize; N],
+    /// Arrival number of the next waiting subscriber.
+    arrival: AtomicUsize,
+    policy: NotifyPolicy,
+    /// Slot to start searching from when waking a single subscriber.
+    cursor: AtomicUsize,
+}
+
+impl<T: Atom, const N: usize> AsyncAtomicMulti<T, N> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: BasicAtomic::new(value),
+            generation: AtomicUsize::new(0),
+            wakers: [const { AtomicWaker::new() }; N],
+            taken: [const { AtomicBool::new(false) }; N],
+            armed: [const { AtomicBool::new(false) }; N],
+            priorities: [const { AtomicUsize::new(0) }; N],
+            arrivals: [const { AtomicUsize::new(0) }; N],
+            arrival: AtomicUsize::new(0),
+            policy: NotifyPolicy::All,
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    pub const fn from_impl(repr: <T::Repr as PrimitiveAtom>::Impl) -> Self {
+        Self {
+            value: BasicAtomic::from_impl(repr),
+            generation: AtomicUsize::new(0),
+            wakers: [const { AtomicWaker::new() }; N],
+            taken: [const { AtomicBool::new(false) }; N],
+            armed: [const { AtomicBool::new(false) }; N],
+            priorities: [const { AtomicUsize::new(0) }; N],
+            arrivals: [const { AtomicUsize::new(0) }; N],
+            arrival: AtomicUsize::new(0),
+            policy: NotifyPolicy::All,
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    /// Set policy used by stores that don't specify it explicitly.
+    pub const fn with_policy(mut self, policy: NotifyPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Policy used by stores that don't specify it explicitly.
+    pub fn policy(&self) -> NotifyPolicy {
+        self.policy
+    }
+
+    /// Consume the atomic and return its value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Number of modifications made to the atomic so far (wrapping on overflow).
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Number of subscribers waiting for the next store.
+    ///
+    /// Subscribers that hold a slot but are not polled for a store are not counted.
+    /// Like [`AsyncAtomic::has_waiter`](`crate::AsyncAtomic::has_waiter`) it is only a hint.
+    pub fn waiter_count(&self) -> usize {
+        self.armed
+            .iter()
+            .filter(|armed| armed.load(Ordering::Acquire))
+            .count()
+    }
+
+    /// Whether at least one subscriber is waiting for the next store.
+    pub fn has_waiter(&self) -> bool {
+        self.armed.iter().any(|armed| armed.load(Ordering::Acquire))
+    }
+
+    fn notify(&self) {
+        self.notify_with(self.policy);
+    }
+
+    /// Increment generation and wake subscribers according to `policy`.
+    ///
+    /// Subscribers are woken in the order of decreasing priority,
+    /// subscribers with equal priority are picked in round-robin order.
+    fn notify_with(&self, policy: NotifyPolicy) {
+        self.generation.fetch_add(1, Ordering::Release);
+        // Pairs with the fence in subscriber registration: either the subscriber sees the store or we see it armed.
+        fence(Ordering::SeqCst);
+        if policy == NotifyPolicy::Fifo {
+            self.wake_longest_waiting();
+            return;
+        }
+        let start = self.cursor.fetch_add(1, Ordering::Relaxed);
+        // Priority levels are visited from the highest one, `bound` is the upper limit of the next level.
+        let mut bound = usize::MAX;
+        while let Some(level) = self
+            .priorities
+            .iter()
+            .zip(&self.taken)
+            .filter(|(_, taken)| taken.load(Ordering::Acquire))
+            .map(|(priority, _)| priority.load(Ordering::Relaxed))
+            .filter(|priority| *priority <= bound)
+            .max()
+        {
+            for i in 0..N {
+                let slot = start.wrapping_add(i) % N;
+                if self.priorities[slot].load(Ordering::Relaxed) != level {
+                    continue;
+                }
+                if self.wake_slot(slot) && policy == NotifyPolicy::One {
+                    return;
+                }
+            }
+            match level.checked_sub(1) {
+                Some(next) => bound = next,
+                None => break,
+            }
+        }
+    }
+
+    /// Wake subscriber in `slot` if it is waiting. Returns whether a waker was woken.
+    ///
+    /// The flag is cleared before taking the waker, so a subscriber registering concurrently
+    /// either has its new waker woken or stays armed.
+    fn wake_slot(&self, slot: usize) -> bool {
+        if self.armed[slot].load(Ordering::Relaxed)
+            && self.armed[slot].swap(false, Ordering::Acquire)
+        {
+            if let Some(waker) = self.wakers[slot].take() {
+                waker.wake();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Wake the waiting subscriber with the highest priority that arrived first.
+    fn wake_longest_waiting(&self) {
+        // Each failed attempt disarms the picked slot, so another one is picked next time.
+        for _ in 0..N {
+            let next = self.arrival.load(Ordering::Relaxed);
+            let slot = (0..N)
+                .filter(|&slot| {
+                    self.taken[slot].load(Ordering::Acquire)
+                        && self.armed[slot].load(Ordering::Relaxed)
+                })
+                .max_by_key(|&slot| {
+                    (
+                        self.priorities[slot].load(Ordering::Relaxed),
+                        // Waiting time in arrivals, robust to wrapping.
+                        next.wrapping_sub(self.arrivals[slot].load(Ordering::Relaxed)),
+                    )
+                });
+            match slot {
+                Some(slot) if !self.wake_slot(slot) => continue,
+                _ => break,
+            }
+        }
+    }
+
+    /// Occupy a free waker slot and create subscriber on it.
+    ///
+    /// Returns `None` if all `N` slots are occupied.
+    /// The slot is released when the subscriber is dropped.
+    pub fn try_subscribe(&self) -> Option<MultiSubscriber<'_, T, N>> {
+        self.try_subscribe_with_priority(0)
+    }
+
+    /// Occupy a free waker slot and create subscriber with `priority` on it.
+    ///
+    /// Subscribers with higher priority are woken first,
+    /// and with [`NotifyPolicy::One`] only the highest-priority waiting subscriber is woken.
+    /// Returns `None` if all `N` slots are occupied.
+    pub fn try_subscribe_with_priority(
+        &self,
+        priority: usize,
+    ) -> Option<MultiSubscriber<'_, T, N>> {
+        let slot = self.taken.iter().position(|taken| {
+            taken
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        })?;
+        self.priorities[slot].store(priority, Ordering::Relaxed);
+        Some(MultiSubscriber {
+            atomic: self,
+            slot,
+            seen: self.generation(),
+        })
+    }
+
+    /// Occupy a free waker slot and create subscriber on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all `N` slots are occupied.
+    pub fn subscribe(&self) -> MultiSubscriber<'_, T, N> {
+        self.try_subscribe().expect("all waker slots are occupied")
+    }
+
+    /// Occupy a free waker slot and create subscriber with `priority` on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all `N` slots are occupied.
+    pub fn subscribe_with_priority(&self, priority: usize) -> MultiSubscriber<'_, T, N> {
+        self.try_subscribe_with_priority(priority)
+            .expect("all waker slots are occupied")
+    }
+
+    pub fn load(&self) -> T {
+        self.value.load(Ordering::Acquire)
+    }
+
+    pub fn store(&self, val: T) {
+        self.value.store(val, Ordering::Release);
+        self.notify();
+    }
+
+    /// Store value waking subscribers according to `policy` instead of the configured one.
+    pub fn store_with(&self, val: T, policy: NotifyPolicy) {
+        self.value.store(val, Ordering::Release);
+        self.notify_with(policy);
+    }
+
+    pub fn swap(&self, val: T) -> T {
+        let old = self.value.swap(val, Ordering::AcqRel);
+        self.notify();
+        old
+    }
+
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
+        self.value
+            .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+            .inspect(|_| self.notify())
+    }
+
+    pub fn fetch_update<F: FnMut(T) -> Option<T>>(&self, f: F) -> Result<T, T> {
+        self.value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, f)
+            .inspect(|_| self.notify())
+    }
+}
+
+impl<T: AtomLogic, const N: usize> AsyncAtomicMulti<T, N>
+where
+    T::Repr: PrimitiveAtomLogic,
+{
+    pub fn fetch_and(&self, val: T) -> T {
+        let old = self.value.fetch_and(val, Ordering::AcqRel);
+        self.notify();
+        old
+    }
+    pub fn fetch_or(&self, val: T) -> T {
+        let old = self.value.fetch_or(val, Ordering::AcqRel);
+        self.notify();
+        old
+    }
+    pub fn fetch_xor(&self, val: T) -> T {
+        let old = self.value.fetch_xor(val, Ordering::AcqRel);
+        self.notify();
+        old
+    }
+}
+
+impl<T: AtomInteger, const N: usize> AsyncAtomicMulti<T, N>
+where
+    T::Repr: PrimitiveAtomInteger,
+{
+    pub fn fetch_add(&self, val: T) -> T {
+        let old = self.value.fetch_add(val, Ordering::AcqRel);
+        self.notify();
+        old
+    }
+    pub fn fetch_sub(&self, val: T) -> T {
+        let old = self.value.fetch_sub(val, Ordering::AcqRel);
+        self.notify();
+        old
+    }
+}
+
+impl<T: Atom + Default, const N: usize> Default for AsyncAtomicMulti<T, N> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Atom + fmt::Debug, const N: usize> fmt::Debug for AsyncAtomicMulti<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncAtomicMulti")
+            .field("value", &self.load())
+            .field("generation", &self.generation())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Subscriber that occupies one waker slot of [`AsyncAtomicMulti`].
+///
+/// Each subscriber also remembers its own last seen [`generation`](`AsyncAtomicMulti::generation`),
+/// so every subscriber observes changes independently of others.
+/// Dereferences to the atomic, so it can be used to load and store values too.
+pub struct MultiSubscriber<'a, T: Atom, const N: usize> {
+    atomic: &'a AsyncAtomicMulti<T, N>,
+    slot: usize,
+    pub seen: usize,
+}
+
+impl<'a, T: Atom, const N: usize> MultiSubscriber<'a, T, N> {
+    /// Check whether the atomic was stored to since the last [`mark_seen`](`Self::mark_seen`) (or subscriber creation).
+    ///
+    /// Returns `true` even if the stored value is equal to the previous one.
+    pub fn has_changed(&self) -> bool {
+        self.atomic.generation() != self.seen
+    }
+
+    /// Mark current value as seen.
+    pub fn mark_seen(&mut self) {
+        self.seen = self.atomic.generation();
+    }
+
+    /// Poll for the atomic to be stored to since the last seen generation.
+    ///
+    /// Registers waker from `cx` in the subscriber slot. On success marks the new value as seen and returns it.
+    pub fn poll_updated(&mut self, cx: &mut Context<'_>) -> Poll<T> {
+        self.register(cx.waker());
+        let generation = self.atomic.generation();
+        if generation != self.seen {
+            self.seen = generation;
+            self.unregister();
+            Poll::Ready(self.atomic.load())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Asynchronously wait for the next store since the last seen generation and return the stored value.
+    pub fn updated(&mut self) -> MultiUpdated<'_, 'a, T, N> {
+        MultiUpdated { inner: self }
+    }
+
+    fn register(&self, waker: &Waker) {
+        let atomic = self.atomic;
+        atomic.wakers[self.slot].register(waker);
+        // Only a subscriber that starts waiting gets a new place in the queue.
+        if !atomic.armed[self.slot].load(Ordering::Acquire) {
+            let arrival = atomic.arrival.fetch_add(1, Ordering::Relaxed);
+            atomic.arrivals[self.slot].store(arrival, Ordering::Relaxed);
+        }
+        atomic.armed[self.slot].store(true, Ordering::Relaxed);
+        // Pairs with the fence in `notify_with`: either the subsequent load sees the store or `notify_with` sees the flag.
+        fence(Ordering::SeqCst);
+    }
+
+    /// Remove waker of a subscriber that is not waiting anymore,
+    /// so that [`NotifyPolicy::One`] picks another subscriber.
+    fn unregister(&self) {
+        self.atomic.armed[self.slot].store(false, Ordering::Relaxed);
+        drop(self.atomic.wakers[self.slot].take());
+    }
+
+    /// Poll for predicate to be `true`.
+    ///
+    /// Registers waker from `cx` in the subscriber slot and then checks predicate on the current value.
+    pub fn poll_wait<F: FnMut(T) -> bool>(&self, cx: &mut Context<'_>, mut pred: F) -> Poll<()> {
+        self.register(cx.waker());
+        if pred(self.atomic.load()) {
+            self.unregister();
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Poll for value to be changed compared to `prev`.
+    ///
+    /// Registers waker from `cx` in the subscriber slot. `None` in `prev` means that nothing is seen yet.
+    pub fn poll_changed(&self, cx: &mut Context<'_>, prev: &mut Option<T>) -> Poll<T>
+    where
+        T: PartialEq + Clone,
+    {
+        self.register(cx.waker());
+        let value = self.atomic.load();
+        if prev.replace(value.clone()).is_none_or(|prev| prev != value) {
+            self.unregister();
+            Poll::Ready(value)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Asynchronously wait for predicate to be `true`.
+    pub fn wait<F: FnMut(T) -> bool>(&self, pred: F) -> MultiWait<'_, 'a, T, N, F> {
+        MultiWait { inner: self, pred }
+    }
+
+    /// Convert subscriber into stream that yields when value is changed.
+    ///
+    /// The current value is yielded on the first poll.
+    pub fn changed(self) -> MultiChanged<'a, T, N>
+    where
+        T: PartialEq + Clone,
+    {
+        MultiChanged {
+            inner: self,
+            prev: None,
+        }
+    }
+}
+
+impl<'a, T: Atom, const N: usize> Deref for MultiSubscriber<'a, T, N> {
+    type Target = AsyncAtomicMulti<T, N>;
+    fn deref(&self) -> &Self::Target {
+        self.atomic
+    }
+}
+
+impl<T: Atom, const N: usize> Drop for MultiSubscriber<'_, T, N> {
+    fn drop(&mut self) {
+        self.unregister();
+        self.atomic.taken[self.slot].store(false, Ordering::Release);
+    }
+}
+
+/// Future to wait for predicate on [`AsyncAtomicMulti`].
+pub struct MultiWait<'s, 'a, T: Atom, const N: usize, F: FnMut(T) -> bool> {
+    pub inner: &'s MultiSubscriber<'a, T, N>,
+    pub pred: F,
+}
+
+impl<T: Atom, const N: usize, F: FnMut(T) -> bool> Unpin for MultiWait<'_, '_, T, N, F> {}
+
+impl<T: Atom, const N: usize, F: FnMut(T) -> bool> Drop for MultiWait<'_, '_, T, N, F> {
+    fn drop(&mut self) {
+        self.inner.unregister();
+    }
+}
+
+impl<T: Atom, const N: usize, F: FnMut(T) -> bool> Future for MultiWait<'_, '_, T, N, F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner;
+        inner.poll_wait(cx, &mut self.pred)
+    }
+}
+
+/// Future to wait for the next store to [`AsyncAtomicMulti`].
+pub struct MultiUpdated<'s, 'a, T: Atom, const N: usize> {
+    pub inner: &'s mut MultiSubscriber<'a, T, N>,
+}
+
+impl<T: Atom, const N: usize> Unpin for MultiUpdated<'_, '_, T, N> {}
+
+impl<T: Atom, const N: usize> Drop for MultiUpdated<'_, '_, T, N> {
+    fn drop(&mut self) {
+        self.inner.unregister();
+    }
+}
+
+impl<T: Atom, const N: usize> Future for MultiUpdated<'_, '_, T, N> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.poll_updated(cx)
+    }
+}
+
+/// Stream of changes of [`AsyncAtomicMulti`].
+pub struct MultiChanged<'a, T: Atom, const N: usize> {
+    pub inner: MultiSubscriber<'a, T, N>,
+    pub prev: Option<T>,
+}
+
+impl<T: Atom, const N: usize> Unpin for MultiChanged<'_, T, N> {}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> Future for MultiChanged<'_, T, N> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.inner.poll_changed(cx, &mut this.prev)
+    }
+}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> Stream for MultiChanged<'_, T, N> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<T: Atom + PartialEq + Clone, const N: usize> FusedStream for MultiChanged<'_, T, N> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,36 @@
+/// Implement [`Atom`](`crate::Atom`) for a single-field tuple struct by delegating to the wrapped type.
+///
+/// Marker traits [`AtomLogic`](`crate::AtomLogic`) and [`AtomInteger`](`crate::AtomInteger`)
+/// can be listed after the type to enable bitwise and arithmetic `fetch_*` methods.
+///
+/// ```
+/// use async_atomic::{atom_newtype, AsyncAtomic};
+///
+/// #[derive(Clone, Copy, PartialEq, Debug)]
+/// #[repr(transparent)]
+/// struct Millimeters(u32);
+///
+/// atom_newtype!(Millimeters(u32), AtomLogic, AtomInteger);
+///
+/// let distance = AsyncAtomic::new(Millimeters(10));
+/// distance.fetch_add(Millimeters(5));
+/// assert_eq!(distance.load(), Millimeters(15));
+/// ```
+#[macro_export]
+macro_rules! atom_newtype {
+    ($name:ident($inner:ty) $(, $marker:ident)* $(,)?) => {
+        impl $crate::Atom for $name {
+            type Repr = <$inner as $crate::Atom>::Repr;
+
+            fn pack(self) -> Self::Repr {
+                <$inner as $crate::Atom>::pack(self.0)
+            }
+            fn unpack(src: Self::Repr) -> Self {
+                Self(<$inner as $crate::Atom>::unpack(src))
+            }
+        }
+        $(
+            impl $crate::$marker for $name {}
+        )*
+    };
+}
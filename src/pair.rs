@@ -0,0 +1,141 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, RawBits, Wait};
+use atomig::Atom;
+use core::{fmt, marker::PhantomData, mem::size_of};
+
+/// Two values packed into a single 64-bit atomic, so that both can be updated at once.
+///
+/// Emulates double-width compare-and-swap for values whose representations fit into 64 bits together,
+/// e.g. a pair of `u32` or an `u16` state with an `i32` counter.
+/// Waits can be keyed on either half or on both of them.
+///
+/// Available only on targets that support 64-bit atomics.
+pub struct AtomicPair<A: Atom<Repr: RawBits>, B: Atom<Repr: RawBits>> {
+    inner: AsyncAtomic<u64>,
+    _phantom: PhantomData<(A, B)>,
+}
+
+impl<A: Atom<Repr: RawBits>, B: Atom<Repr: RawBits>> AtomicPair<A, B> {
+    /// Number of bits occupied by the first value.
+    const SHIFT: u32 = 8 * size_of::<A::Repr>() as u32;
+    const FITS: () = assert!(
+        size_of::<A::Repr>() + size_of::<B::Repr>() <= size_of::<u64>(),
+        "pair doesn't fit into 64 bits"
+    );
+
+    fn pack(a: A, b: B) -> u64 {
+        let low = a.pack().to_bits() & (u64::MAX >> (64 - Self::SHIFT));
+        low | (b.pack().to_bits() << Self::SHIFT)
+    }
+
+    fn unpack(bits: u64) -> (A, B) {
+        (
+            A::unpack(A::Repr::from_bits(bits)),
+            B::unpack(B::Repr::from_bits(bits >> Self::SHIFT)),
+        )
+    }
+
+    pub fn new(a: A, b: B) -> Self {
+        let () = Self::FITS;
+        Self {
+            inner: AsyncAtomic::new(Self::pack(a, b)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Underlying atomic containing packed pair.
+    pub fn as_packed(&self) -> &AsyncAtomic<u64> {
+        &self.inner
+    }
+
+    pub fn load(&self) -> (A, B) {
+        Self::unpack(self.inner.load())
+    }
+
+    pub fn store(&self, a: A, b: B) {
+        self.inner.store(Self::pack(a, b));
+    }
+
+    pub fn swap(&self, a: A, b: B) -> (A, B) {
+        Self::unpack(self.inner.swap(Self::pack(a, b)))
+    }
+
+    /// Replace both values if both of them are equal to `current`.
+    ///
+    /// Values are compared by their representations.
+    pub fn compare_exchange(&self, current: (A, B), new: (A, B)) -> Result<(A, B), (A, B)> {
+        self.inner
+            .compare_exchange(Self::pack(current.0, current.1), Self::pack(new.0, new.1))
+            .map(Self::unpack)
+            .map_err(Self::unpack)
+    }
+
+    pub fn fetch_update<F: FnMut(A, B) -> Option<(A, B)>>(
+        &self,
+        mut f: F,
+    ) -> Result<(A, B), (A, B)> {
+        self.inner
+            .fetch_update(|bits| {
+                let (a, b) = Self::unpack(bits);
+                f(a, b).map(|(a, b)| Self::pack(a, b))
+            })
+            .map(Self::unpack)
+            .map_err(Self::unpack)
+    }
+
+    /// Replace the first value keeping the second one.
+    pub fn store_first(&self, a: A) -> (A, B) {
+        let repr = a.pack();
+        let (Ok(old) | Err(old)) = self.fetch_update(|_, b| Some((A::unpack(repr), b)));
+        old
+    }
+
+    /// Replace the second value keeping the first one.
+    pub fn store_second(&self, b: B) -> (A, B) {
+        let repr = b.pack();
+        let (Ok(old) | Err(old)) = self.fetch_update(|a, _| Some((a, B::unpack(repr))));
+        old
+    }
+
+    /// Asynchronously wait for predicate on both values to be `true`.
+    pub fn wait<F: FnMut(A, B) -> bool>(
+        &self,
+        mut pred: F,
+    ) -> Wait<&AsyncAtomic<u64>, impl FnMut(u64) -> bool> {
+        self.inner.wait(move |bits| {
+            let (a, b) = Self::unpack(bits);
+            pred(a, b)
+        })
+    }
+
+    /// Asynchronously wait for predicate on the first value to be `true`.
+    pub fn wait_first<F: FnMut(A) -> bool>(
+        &self,
+        mut pred: F,
+    ) -> Wait<&AsyncAtomic<u64>, impl FnMut(u64) -> bool> {
+        self.wait(move |a, _| pred(a))
+    }
+
+    /// Asynchronously wait for predicate on the second value to be `true`.
+    pub fn wait_second<F: FnMut(B) -> bool>(
+        &self,
+        mut pred: F,
+    ) -> Wait<&AsyncAtomic<u64>, impl FnMut(u64) -> bool> {
+        self.wait(move |_, b| pred(b))
+    }
+}
+
+impl<A: Atom<Repr: RawBits> + Default, B: Atom<Repr: RawBits> + Default> Default
+    for AtomicPair<A, B>
+{
+    fn default() -> Self {
+        Self::new(A::default(), B::default())
+    }
+}
+
+impl<A: Atom<Repr: RawBits> + fmt::Debug, B: Atom<Repr: RawBits> + fmt::Debug> fmt::Debug
+    for AtomicPair<A, B>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AtomicPair").field(&self.load()).finish()
+    }
+}
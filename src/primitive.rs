@@ -0,0 +1,117 @@
+use crate::{AsyncAtomic, AsyncAtomicMulti};
+use core::sync::atomic::*;
+
+macro_rules! impl_primitive {
+    ($($width:literal: $alias:ident = $t:ty, $impl:ty;)*) => {
+        $(
+            #[cfg(target_has_atomic = $width)]
+            pub type $alias = AsyncAtomic<$t>;
+
+            #[cfg(target_has_atomic = $width)]
+            impl AsyncAtomic<$t> {
+                /// Create atomic in `const` context, e.g. to initialize a `static`.
+                ///
+                /// It is named differently from [`new`](`Self::new`) because generic constructor cannot be `const`.
+                pub const fn const_new(value: $t) -> Self {
+                    Self::from_impl(<$impl>::new(value))
+                }
+            }
+
+            #[cfg(target_has_atomic = $width)]
+            impl<const N: usize> AsyncAtomicMulti<$t, N> {
+                /// Create atomic in `const` context, e.g. to initialize a `static`.
+                pub const fn const_new(value: $t) -> Self {
+                    Self::from_impl(<$impl>::new(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    "8": AsyncAtomicBool = bool, AtomicBool;
+    "8": AsyncAtomicU8 = u8, AtomicU8;
+    "8": AsyncAtomicI8 = i8, AtomicI8;
+    "16": AsyncAtomicU16 = u16, AtomicU16;
+    "16": AsyncAtomicI16 = i16, AtomicI16;
+    "32": AsyncAtomicU32 = u32, AtomicU32;
+    "32": AsyncAtomicI32 = i32, AtomicI32;
+    "64": AsyncAtomicU64 = u64, AtomicU64;
+    "64": AsyncAtomicI64 = i64, AtomicI64;
+    "ptr": AsyncAtomicUsize = usize, AtomicUsize;
+    "ptr": AsyncAtomicIsize = isize, AtomicIsize;
+}
+
+/// Async atomic raw pointer.
+///
+/// Useful for lock-free structures that need to await pointer publication.
+#[cfg(target_has_atomic = "ptr")]
+pub type AsyncAtomicPtr<T> = AsyncAtomic<*mut T>;
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> AsyncAtomic<*mut T> {
+    /// Create atomic pointer in `const` context, e.g. to initialize a `static`.
+    pub const fn const_new(value: *mut T) -> Self {
+        Self::from_impl(AtomicPtr::new(value))
+    }
+}
+
+/// Declare `static` async atomics of primitive types.
+///
+/// Besides the static itself, typed accessor functions are generated for each atomic.
+/// They are associated with an uninhabited type of the same name, so they are called like `FRAMES::load()`.
+///
+/// ```
+/// use async_atomic::async_atomic;
+///
+/// async_atomic! {
+///     /// Number of rendered frames.
+///     pub static FRAMES: u64 = 0;
+///     static READY: bool = false;
+/// }
+///
+/// FRAMES.fetch_add(1);
+/// assert_eq!(FRAMES.load(), 1);
+/// READY::store(true);
+/// assert!(READY::load());
+/// ```
+#[macro_export]
+macro_rules! async_atomic {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $value:expr;)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::AsyncAtomic<$t> = $crate::AsyncAtomic::<$t>::const_new($value);
+
+            /// Typed accessors of the static async atomic with the same name.
+            #[allow(non_camel_case_types, clippy::upper_case_acronyms, dead_code)]
+            $vis enum $name {}
+
+            #[allow(dead_code)]
+            impl $name {
+                /// Reference to the atomic.
+                pub fn atomic() -> &'static $crate::AsyncAtomic<$t> {
+                    &$name
+                }
+
+                pub fn load() -> $t {
+                    $name.load()
+                }
+
+                pub fn store(value: $t) {
+                    $name.store(value);
+                }
+
+                pub fn swap(value: $t) -> $t {
+                    $name.swap(value)
+                }
+
+                /// Asynchronously wait for predicate to be `true`.
+                pub fn wait<F: FnMut($t) -> bool>(
+                    pred: F,
+                ) -> $crate::Wait<&'static $crate::AsyncAtomic<$t>, F> {
+                    $crate::AsyncAtomicRef::wait(&$name, pred)
+                }
+            }
+        )*
+    };
+}
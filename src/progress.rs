@@ -0,0 +1,107 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, MappedChanged, Wait};
+use atomig::Atom;
+
+/// Number of done work items and the total number of them packed into a single atomic.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ProgressState {
+    pub done: u32,
+    pub total: u32,
+}
+
+impl ProgressState {
+    /// Integer percentage of done work, `0` if total is unknown.
+    pub fn percent(self) -> u8 {
+        match self.total {
+            0 => 0,
+            total => (u64::from(self.done.min(total)) * 100 / u64::from(total)) as u8,
+        }
+    }
+
+    /// Whether all the work is done.
+    pub fn is_finished(self) -> bool {
+        self.total != 0 && self.done >= self.total
+    }
+}
+
+impl Atom for ProgressState {
+    type Repr = u64;
+
+    fn pack(self) -> u64 {
+        (u64::from(self.total) << 32) | u64::from(self.done)
+    }
+    fn unpack(src: u64) -> Self {
+        Self {
+            done: src as u32,
+            total: (src >> 32) as u32,
+        }
+    }
+}
+
+/// Progress of work done by worker tasks, e.g. to feed a progress bar.
+///
+/// Available only on targets that support 64-bit atomics.
+#[derive(Default, Debug)]
+pub struct Progress {
+    state: AsyncAtomic<ProgressState>,
+}
+
+impl Progress {
+    pub fn new(total: u32) -> Self {
+        Self {
+            state: AsyncAtomic::new(ProgressState { done: 0, total }),
+        }
+    }
+
+    /// Underlying atomic state.
+    pub fn state(&self) -> &AsyncAtomic<ProgressState> {
+        &self.state
+    }
+
+    pub fn load(&self) -> ProgressState {
+        self.state.load()
+    }
+
+    /// Integer percentage of done work, `0` if total is unknown.
+    pub fn percent(&self) -> u8 {
+        self.load().percent()
+    }
+
+    /// Mark one more work item as done.
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    /// Mark `n` more work items as done (saturating).
+    pub fn inc_by(&self, n: u32) {
+        let _ = self.state.fetch_update(|ProgressState { done, total }| {
+            Some(ProgressState {
+                done: done.saturating_add(n),
+                total,
+            })
+        });
+    }
+
+    /// Set the total number of work items, e.g. when it becomes known.
+    pub fn set_total(&self, total: u32) {
+        let _ = self
+            .state
+            .fetch_update(|ProgressState { done, .. }| Some(ProgressState { done, total }));
+    }
+
+    /// Stream that yields percentage only when it is changed.
+    ///
+    /// The current percentage is yielded on the first poll.
+    pub fn changed(
+        &self,
+    ) -> MappedChanged<&AsyncAtomic<ProgressState>, fn(ProgressState) -> u8, u8> {
+        (&self.state)
+            .mapped(ProgressState::percent as fn(ProgressState) -> u8)
+            .changed()
+    }
+
+    /// Asynchronously wait for all the work to be done.
+    pub fn wait_finished(&self) -> Wait<&AsyncAtomic<ProgressState>, fn(ProgressState) -> bool> {
+        self.state.wait(ProgressState::is_finished)
+    }
+}
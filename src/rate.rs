@@ -0,0 +1,76 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, Counter, Timer, Wait};
+use core::time::Duration;
+
+/// Event rate meter.
+///
+/// Producers [`record`](`Self::record`) events while [`measure`](`Self::measure`) task
+/// periodically computes the number of events per window using [`Timer`].
+/// Consumers read the last measured [`rate`](`Self::rate`) or wait for it to cross a threshold.
+#[derive(Default, Debug)]
+pub struct RateMeter {
+    events: AsyncAtomic<usize>,
+    rate: AsyncAtomic<usize>,
+}
+
+impl RateMeter {
+    pub const fn new() -> Self {
+        Self {
+            events: AsyncAtomic::<usize>::const_new(0),
+            rate: AsyncAtomic::<usize>::const_new(0),
+        }
+    }
+
+    /// Record a single event.
+    pub fn record(&self) {
+        self.record_n(1);
+    }
+
+    /// Record `n` events at once.
+    pub fn record_n(&self, n: usize) {
+        self.events.fetch_add(n);
+    }
+
+    /// Total number of recorded events (wrapping on overflow).
+    pub fn events(&self) -> usize {
+        self.events.load()
+    }
+
+    /// Number of events during the last measured window.
+    pub fn rate(&self) -> usize {
+        self.rate.load()
+    }
+
+    /// Underlying atomic rate, e.g. to subscribe to its changes.
+    pub fn rate_atomic(&self) -> &AsyncAtomic<usize> {
+        &self.rate
+    }
+
+    /// Measure rate every `window` forever.
+    ///
+    /// This future should be spawned as a separate task.
+    pub async fn measure<T: Timer + ?Sized>(&self, timer: &T, window: Duration) {
+        let mut prev = self.events.load();
+        loop {
+            timer.sleep(window).await;
+            let events = self.events.load();
+            self.rate.store(events.delta(prev));
+            prev = events;
+        }
+    }
+
+    /// Asynchronously wait for the measured rate to be above `threshold`.
+    pub fn wait_rate_above(
+        &self,
+        threshold: usize,
+    ) -> Wait<&AsyncAtomic<usize>, impl FnMut(usize) -> bool> {
+        self.rate.wait(move |x| x > threshold)
+    }
+
+    /// Asynchronously wait for the measured rate to be below `threshold`.
+    pub fn wait_rate_below(
+        &self,
+        threshold: usize,
+    ) -> Wait<&AsyncAtomic<usize>, impl FnMut(usize) -> bool> {
+        self.rate.wait(move |x| x < threshold)
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{AsyncAtomic, AsyncAtomicRef};
+use atomig::Atom;
+use core::{
+    array,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+use futures::stream::{FusedStream, Stream};
+
+/// Node of a reactive graph that other nodes can depend on.
+pub trait Dependency: Sync {
+    /// Number of changes of the node so far (wrapping on overflow).
+    fn generation(&self) -> usize;
+
+    /// Register waker to be woken when the node may have changed.
+    ///
+    /// *This occupies the waker of underlying atomics.*
+    fn register(&self, waker: &Waker);
+}
+
+impl<T: Atom> Dependency for AsyncAtomic<T> {
+    fn generation(&self) -> usize {
+        AsyncAtomic::generation(self)
+    }
+    fn register(&self, waker: &Waker) {
+        AsyncAtomic::register(self, waker)
+    }
+}
+
+/// Value computed from other nodes of reactive graph.
+///
+/// The value is recomputed lazily when it is read after any of dependencies changed,
+/// or eagerly by a task running [`drive`](`Self::drive`).
+/// Computed node is a [`Dependency`] itself, so nodes can be chained.
+/// Subscribers of the computed atomic (see [`AsyncAtomicRef`]) are notified only when the computed value actually changes.
+///
+/// ```
+/// use async_atomic::{AsyncAtomic, Computed};
+///
+/// let width = AsyncAtomic::new(2);
+/// let height = AsyncAtomic::new(3);
+/// let area = Computed::new([&width, &height], || width.load() * height.load());
+/// let double = Computed::new([&area], || area.load() * 2);
+///
+/// width.store(4);
+/// assert_eq!(double.load(), 24);
+/// ```
+pub struct Computed<'a, T: Atom + PartialEq, F: Fn() -> T, const N: usize> {
+    deps: [&'a dyn Dependency; N],
+    seen: [AtomicUsize; N],
+    compute: F,
+    output: AsyncAtomic<T>,
+}
+
+impl<'a, T: Atom + PartialEq, F: Fn() -> T, const N: usize> Computed<'a, T, F, N> {
+    /// Create node that depends on `deps` and compute initial value.
+    ///
+    /// `compute` must read only the nodes listed in `deps`.
+    pub fn new(deps: [&'a dyn Dependency; N], compute: F) -> Self {
+        let seen = array::from_fn(|i| AtomicUsize::new(deps[i].generation()));
+        let output = AsyncAtomic::new(compute());
+        Self {
+            deps,
+            seen,
+            compute,
+            output,
+        }
+    }
+
+    fn is_outdated(&self) -> bool {
+        self.deps
+            .iter()
+            .zip(&self.seen)
+            .any(|(dep, seen)| dep.generation() != seen.load(Ordering::Acquire))
+    }
+
+    /// Recompute value if any of dependencies changed. Returns `true` if the value has changed.
+    pub fn refresh(&self) -> bool {
+        let mut outdated = false;
+        for (dep, seen) in self.deps.iter().zip(&self.seen) {
+            let generation = dep.generation();
+            if seen.swap(generation, Ordering::AcqRel) != generation {
+                outdated = true;
+            }
+        }
+        if !outdated {
+            return false;
+        }
+        let value = (self.compute)();
+        if self.output.load() != value {
+            self.output.store(value);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get up-to-date value.
+    pub fn load(&self) -> T {
+        self.refresh();
+        self.output.load()
+    }
+
+    /// Recompute value eagerly whenever any of dependencies changes.
+    ///
+    /// This future never completes, it should be spawned as a separate task.
+    pub async fn drive(&self) {
+        loop {
+            poll_fn(|cx| {
+                self.deps.iter().for_each(|dep| dep.register(cx.waker()));
+                if self.is_outdated() {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await;
+            self.refresh();
+        }
+    }
+}
+
+impl<T: Atom + PartialEq, F: Fn() -> T + Sync, const N: usize> Dependency
+    for Computed<'_, T, F, N>
+{
+    fn generation(&self) -> usize {
+        self.refresh();
+        self.output.generation()
+    }
+    fn register(&self, waker: &Waker) {
+        self.deps.iter().for_each(|dep| dep.register(waker));
+        self.output.register(waker);
+    }
+}
+
+impl<T: Atom + PartialEq, F: Fn() -> T, const N: usize> AsyncAtomicRef for Computed<'_, T, F, N> {
+    type Item = T;
+    fn as_atomic(&self) -> &AsyncAtomic<T> {
+        &self.output
+    }
+}
+
+/// Cached `f(value)` of the source atomic.
+///
+/// The cache is invalidated on every store into the source and recomputed on the next access,
+/// at most once per source [`generation`](`AsyncAtomic::generation`).
+/// Unlike [`Mapped`](`crate::Mapped`), waiting on the memo resolves only when the derived value changes.
+pub struct Memo<R: AsyncAtomicRef, U: Atom + PartialEq, F: Fn(R::Item) -> U> {
+    source: R,
+    map: F,
+    cache: AsyncAtomic<U>,
+    seen: AtomicUsize,
+}
+
+impl<R: AsyncAtomicRef, U: Atom + PartialEq, F: Fn(R::Item) -> U> Memo<R, U, F> {
+    pub fn new(source: R, map: F) -> Self {
+        let atomic = source.as_atomic();
+        let seen = AtomicUsize::new(atomic.generation());
+        let cache = AsyncAtomic::new(map(atomic.load()));
+        Self {
+            source,
+            map,
+            cache,
+            seen,
+        }
+    }
+
+    /// Source atomic.
+    pub fn source(&self) -> &R {
+        &self.source
+    }
+
+    /// Recompute cached value if the source has changed. Returns `true` if the derived value has changed.
+    pub fn refresh(&self) -> bool {
+        let atomic = self.source.as_atomic();
+        let generation = atomic.generation();
+        let seen = self.seen.load(Ordering::Acquire);
+        if seen == generation
+            || self
+                .seen
+                .compare_exchange(seen, generation, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+        {
+            // Up to date or someone else is already recomputing this generation.
+            return false;
+        }
+        let value = (self.map)(atomic.load());
+        if self.cache.load() != value {
+            self.cache.store(value);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get up-to-date derived value.
+    pub fn get(&self) -> U {
+        self.refresh();
+        self.cache.load()
+    }
+
+    /// Stream that yields derived value when it changes, starting from the current one.
+    pub fn changed(&self) -> MemoChanged<'_, R, U, F> {
+        MemoChanged {
+            memo: self,
+            prev: None,
+        }
+    }
+
+    /// Wait until derived value satisfies the predicate.
+    pub async fn wait<P: FnMut(U) -> bool>(&self, mut pred: P) -> U
+    where
+        U: Clone,
+    {
+        let mut changed = self.changed();
+        loop {
+            let value = (&mut changed).await;
+            if pred(value.clone()) {
+                break value;
+            }
+        }
+    }
+}
+
+/// Stream that yields derived value of [`Memo`] when it changes.
+pub struct MemoChanged<'a, R: AsyncAtomicRef, U: Atom + PartialEq, F: Fn(R::Item) -> U> {
+    pub memo: &'a Memo<R, U, F>,
+    pub prev: Option<U>,
+}
+
+impl<R: AsyncAtomicRef, U: Atom + PartialEq, F: Fn(R::Item) -> U> Unpin
+    for MemoChanged<'_, R, U, F>
+{
+}
+
+impl<R: AsyncAtomicRef, U: Atom + PartialEq, F: Fn(R::Item) -> U> Drop
+    for MemoChanged<'_, R, U, F>
+{
+    fn drop(&mut self) {
+        self.memo.source.as_atomic().release_dropped();
+    }
+}
+
+impl<R: AsyncAtomicRef, U: Atom + PartialEq + Clone, F: Fn(R::Item) -> U> Future
+    for MemoChanged<'_, R, U, F>
+{
+    type Output = U;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.memo.source.as_atomic().register(cx.waker());
+        let value = self.memo.get();
+        if self
+            .prev
+            .replace(value.clone())
+            .is_none_or(|prev| prev != value)
+        {
+            Poll::Ready(value)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef, U: Atom + PartialEq + Clone, F: Fn(R::Item) -> U> Stream
+    for MemoChanged<'_, R, U, F>
+{
+    type Item = U;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<U>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+impl<R: AsyncAtomicRef, U: Atom + PartialEq + Clone, F: Fn(R::Item) -> U> FusedStream
+    for MemoChanged<'_, R, U, F>
+{
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,117 @@
+use crate::AsyncAtomic;
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use atomig::Atom;
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Collection of async atomics indexed by keys, e.g. per-connection or per-device state.
+///
+/// Atomics are reference-counted, so a handle or a pending [`wait_any`](`Self::wait_any`)
+/// stays valid after the key is removed from the registry.
+/// Insertion and removal require unique access, so wrap the registry into a lock to share it between tasks.
+#[derive(Debug)]
+pub struct AtomicRegistry<K: Ord, T: Atom> {
+    map: BTreeMap<K, Arc<AsyncAtomic<T>>>,
+}
+
+impl<K: Ord, T: Atom> Default for AtomicRegistry<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, T: Atom> AtomicRegistry<K, T> {
+    pub fn new() -> Self {
+        Self {
+            map: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Insert new atomic with `value` for `key` and return it.
+    ///
+    /// If the key is already present then its atomic is replaced.
+    pub fn insert(&mut self, key: K, value: T) -> Arc<AsyncAtomic<T>> {
+        let atomic = Arc::new(AsyncAtomic::new(value));
+        self.map.insert(key, atomic.clone());
+        atomic
+    }
+
+    /// Remove atomic for `key` from the registry and return it.
+    pub fn remove(&mut self, key: &K) -> Option<Arc<AsyncAtomic<T>>> {
+        self.map.remove(key)
+    }
+
+    /// Get atomic for `key`.
+    pub fn get(&self, key: &K) -> Option<&Arc<AsyncAtomic<T>>> {
+        self.map.get(key)
+    }
+
+    /// Iterate over keys and atomics in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &Arc<AsyncAtomic<T>>)> {
+        self.map.iter()
+    }
+
+    /// Asynchronously wait for predicate to be `true` for any atomic from `keys` and resolve with its key and value.
+    ///
+    /// Keys that are not present in the registry are ignored.
+    /// The future holds atomics by itself, so it doesn't borrow the registry.
+    ///
+    /// *It occupies wakers of all the atomics, so it should not be mixed with other subscriptions of them.*
+    pub fn wait_any<I: IntoIterator<Item = K>, F: FnMut(&K, T) -> bool>(
+        &self,
+        keys: I,
+        pred: F,
+    ) -> WaitAny<K, T, F> {
+        let atomics = keys
+            .into_iter()
+            .filter_map(|key| {
+                let atomic = self.map.get(&key)?.clone();
+                Some((key, atomic))
+            })
+            .collect();
+        WaitAny { atomics, pred }
+    }
+}
+
+/// Future to wait for predicate on any of several atomics.
+pub struct WaitAny<K, T: Atom, F: FnMut(&K, T) -> bool> {
+    pub atomics: Vec<(K, Arc<AsyncAtomic<T>>)>,
+    pub pred: F,
+}
+
+impl<K, T: Atom, F: FnMut(&K, T) -> bool> Unpin for WaitAny<K, T, F> {}
+
+impl<K, T: Atom, F: FnMut(&K, T) -> bool> Drop for WaitAny<K, T, F> {
+    fn drop(&mut self) {
+        self.atomics
+            .iter()
+            .for_each(|(_, atomic)| atomic.release_dropped());
+    }
+}
+
+impl<K: Clone, T: Atom + Clone, F: FnMut(&K, T) -> bool> Future for WaitAny<K, T, F> {
+    type Output = (K, T);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        for (key, atomic) in &this.atomics {
+            atomic.register(cx.waker());
+            let value = atomic.load();
+            if (this.pred)(key, value.clone()) {
+                return Poll::Ready((key.clone(), value));
+            }
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{AsyncAtomicRef, Changed};
+use core::{
+    future::Future,
+    pin::{pin, Pin},
+    task::{Context, Poll},
+};
+use futures::stream::StreamExt;
+use futures_signals::signal::{Signal, SignalExt};
+
+/// Stream of changes is a signal: it yields the current value first and then every change.
+impl<R: AsyncAtomicRef<Item: PartialEq + Clone>> Signal for Changed<R> {
+    type Item = R::Item;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll(cx).map(Some)
+    }
+}
+
+/// Store every value of `signal` into atomic.
+///
+/// Resolves when the signal ends.
+pub async fn forward_from_signal<R: AsyncAtomicRef, S: Signal<Item = R::Item>>(
+    signal: S,
+    atomic: R,
+) {
+    let mut values = pin!(signal.to_stream());
+    while let Some(value) = values.next().await {
+        atomic.as_atomic().store(value);
+    }
+}
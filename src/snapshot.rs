@@ -0,0 +1,77 @@
+use crate::AsyncAtomicRef;
+use core::hint;
+
+/// Tuple of atomics that can be read together, see [`snapshot`].
+pub trait Snapshot {
+    /// Tuple of values.
+    type Values;
+    /// Generations of all atomics.
+    type Generations: PartialEq;
+
+    fn generations(&self) -> Self::Generations;
+
+    /// Load all values one by one without consistency check.
+    fn load_all(&self) -> Self::Values;
+}
+
+macro_rules! impl_snapshot {
+    ($($n:literal: $($r:ident . $i:tt),+;)*) => {
+        $(
+            impl<$($r: AsyncAtomicRef),+> Snapshot for ($($r,)+) {
+                type Values = ($($r::Item,)+);
+                type Generations = [usize; $n];
+
+                fn generations(&self) -> Self::Generations {
+                    [$(self.$i.as_atomic().generation()),+]
+                }
+                fn load_all(&self) -> Self::Values {
+                    ($(self.$i.as_atomic().load(),)+)
+                }
+            }
+        )*
+    };
+}
+
+impl_snapshot!(
+    1: A.0;
+    2: A.0, B.1;
+    3: A.0, B.1, C.2;
+    4: A.0, B.1, C.2, D.3;
+    5: A.0, B.1, C.2, D.3, E.4;
+    6: A.0, B.1, C.2, D.3, E.4, F.5;
+);
+
+/// Read several atomics retrying until their generations didn't change while reading.
+///
+/// Useful for checking invariants spanning multiple atomics.
+/// Spins while atomics are concurrently modified, so it's not suitable for atomics that are stored to continuously.
+///
+/// *The values are not guaranteed to be mutually consistent.*
+/// *A store writes the value before bumping the generation, so a store that is in progress during the read
+/// may be already visible in the values but not yet in the generations.*
+/// *Retrying only filters out stores that completed while reading.*
+pub fn snapshot<S: Snapshot>(atomics: S) -> S::Values {
+    loop {
+        let before = atomics.generations();
+        let values = atomics.load_all();
+        if atomics.generations() == before {
+            break values;
+        }
+        hint::spin_loop();
+    }
+}
+
+/// Read a tuple of atomics retrying on concurrent stores, see [`snapshot`](`fn@crate::snapshot`).
+///
+/// ```
+/// use async_atomic::{snapshot, AsyncAtomic};
+///
+/// let (a, b) = (AsyncAtomic::new(1), AsyncAtomic::new(false));
+/// assert_eq!(snapshot!((a, b)), (1, false));
+/// ```
+#[macro_export]
+macro_rules! snapshot {
+    (($($atomic:expr),+ $(,)?)) => {
+        $crate::snapshot(($(&$atomic,)+))
+    };
+}
@@ -0,0 +1,251 @@
+use crate::AsyncAtomic;
+use alloc::sync::Arc;
+use atomig::Atom;
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+use futures::stream::{FusedStream, Stream};
+
+/// Error returned by [`Producer::try_store`] when the consumer is closed. Contains the value that wasn't stored.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Closed<T>(pub T);
+
+impl<T> fmt::Display for Closed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "consumer is closed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for Closed<T> {}
+
+struct Shared<T: Atom> {
+    atomic: AsyncAtomic<T>,
+    producers: AtomicUsize,
+    subscribers: AtomicUsize,
+    closed: AtomicBool,
+}
+
+impl<T: Atom> Shared<T> {
+    fn producer_count(&self) -> usize {
+        self.producers.load(Ordering::Acquire)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.subscribers.load(Ordering::Acquire)
+    }
+}
+
+/// Create atomic with `value` split into producer and consumer handles.
+///
+/// Producer can be cloned to get multiple writers, while there is only a single consumer
+/// because the atomic has a single waker.
+/// When all producers are dropped the consumer streams terminate.
+pub fn split<T: Atom>(value: T) -> (Producer<T>, Consumer<T>) {
+    let shared = Arc::new(Shared {
+        atomic: AsyncAtomic::new(value),
+        producers: AtomicUsize::new(1),
+        subscribers: AtomicUsize::new(1),
+        closed: AtomicBool::new(false),
+    });
+    (
+        Producer {
+            shared: shared.clone(),
+        },
+        Consumer { shared },
+    )
+}
+
+/// Writing handle of [`split`] atomic.
+pub struct Producer<T: Atom> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Atom> Producer<T> {
+    pub fn load(&self) -> T {
+        self.shared.atomic.load()
+    }
+
+    pub fn store(&self, val: T) {
+        self.shared.atomic.store(val);
+    }
+
+    /// Store value unless the consumer is closed.
+    ///
+    /// The check is not atomic with the store, so a value stored concurrently with closing may be never read.
+    pub fn try_store(&self, val: T) -> Result<(), Closed<T>> {
+        if self.is_closed() {
+            Err(Closed(val))
+        } else {
+            self.store(val);
+            Ok(())
+        }
+    }
+
+    /// Whether the consumer is closed or dropped, so nobody will read the value anymore.
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::Acquire)
+    }
+
+    /// Number of live producers including this one.
+    pub fn producer_count(&self) -> usize {
+        self.shared.producer_count()
+    }
+
+    /// Number of live consumers, i.e. `1` if the consumer is not dropped yet, otherwise `0`.
+    pub fn subscriber_count(&self) -> usize {
+        self.shared.subscriber_count()
+    }
+
+    pub fn swap(&self, val: T) -> T {
+        self.shared.atomic.swap(val)
+    }
+
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
+        self.shared.atomic.compare_exchange(current, new)
+    }
+
+    pub fn fetch_update<F: FnMut(T) -> Option<T>>(&self, f: F) -> Result<T, T> {
+        self.shared.atomic.fetch_update(f)
+    }
+}
+
+impl<T: Atom> Clone for Producer<T> {
+    fn clone(&self) -> Self {
+        self.shared.producers.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: Atom> Drop for Producer<T> {
+    fn drop(&mut self) {
+        if self.shared.producers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.atomic.wake();
+        }
+    }
+}
+
+impl<T: Atom + fmt::Debug> fmt::Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Producer").field(&self.load()).finish()
+    }
+}
+
+/// Reading handle of [`split`] atomic.
+pub struct Consumer<T: Atom> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Atom> Consumer<T> {
+    pub fn load(&self) -> T {
+        self.shared.atomic.load()
+    }
+
+    /// Tell producers that the value will not be read anymore, see [`Producer::try_store`].
+    ///
+    /// Dropping the consumer closes it too.
+    pub fn close(&self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+
+    /// Whether all producers are dropped, so the value will never change anymore.
+    pub fn is_finished(&self) -> bool {
+        self.producer_count() == 0
+    }
+
+    /// Number of live producers.
+    pub fn producer_count(&self) -> usize {
+        self.shared.producer_count()
+    }
+
+    /// Convert consumer into stream that yields value when it is changed.
+    ///
+    /// The current value is yielded on the first poll.
+    /// The stream terminates when all producers are dropped and the last value is yielded.
+    pub fn changed(self) -> ConsumerChanged<T>
+    where
+        T: PartialEq + Clone,
+    {
+        ConsumerChanged {
+            inner: self,
+            prev: None,
+            terminated: false,
+        }
+    }
+}
+
+impl<T: Atom> Drop for Consumer<T> {
+    fn drop(&mut self) {
+        self.close();
+        self.shared.subscribers.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl<T: Atom + fmt::Debug> fmt::Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Consumer").field(&self.load()).finish()
+    }
+}
+
+/// Stream that yields value of [`Consumer`] when it change until all producers are dropped.
+pub struct ConsumerChanged<T: Atom + PartialEq + Clone> {
+    pub inner: Consumer<T>,
+    pub prev: Option<T>,
+    terminated: bool,
+}
+
+impl<T: Atom + PartialEq + Clone> Unpin for ConsumerChanged<T> {}
+
+impl<T: Atom + PartialEq + Clone> Drop for ConsumerChanged<T> {
+    fn drop(&mut self) {
+        self.inner.shared.atomic.release_dropped();
+    }
+}
+
+impl<T: Atom + PartialEq + Clone> Stream for ConsumerChanged<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        let atomic = &self.inner.shared.atomic;
+        atomic.register(cx.waker());
+        // Check producers before loading value to not miss the last store.
+        let finished = self.inner.is_finished();
+        let value = atomic.load();
+        if self
+            .prev
+            .replace(value.clone())
+            .is_none_or(|prev| prev != value)
+        {
+            Poll::Ready(Some(value))
+        } else if finished {
+            self.terminated = true;
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: Atom + PartialEq + Clone> FusedStream for ConsumerChanged<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T: Atom + PartialEq + Clone> Future for ConsumerChanged<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_next(cx)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, Wait};
+use atomig::Atom;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Number of recorded samples and their sum packed into a single atomic.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Totals {
+    /// Number of samples (saturating at `u32::MAX`).
+    pub count: u32,
+    /// Sum of samples (saturating at `u32::MAX`).
+    pub sum: u32,
+}
+
+impl Atom for Totals {
+    type Repr = u64;
+
+    fn pack(self) -> u64 {
+        (u64::from(self.count) << 32) | u64::from(self.sum)
+    }
+    fn unpack(src: u64) -> Self {
+        Self {
+            count: (src >> 32) as u32,
+            sum: src as u32,
+        }
+    }
+}
+
+/// Statistics of recorded samples.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StatsSnapshot {
+    /// Number of samples (saturating at `u32::MAX`).
+    pub count: u32,
+    /// Sum of samples (saturating at `u32::MAX`).
+    pub sum: u32,
+    /// Minimal sample or `None` if nothing was recorded.
+    pub min: Option<u32>,
+    /// Maximal sample or `None` if nothing was recorded.
+    pub max: Option<u32>,
+}
+
+impl StatsSnapshot {
+    /// Whether the sum or the count has reached `u32::MAX` and may be lower than the actual one.
+    pub fn is_saturated(&self) -> bool {
+        self.sum == u32::MAX || self.count == u32::MAX
+    }
+
+    /// Mean of samples or `None` if nothing was recorded or the totals are [saturated](`Self::is_saturated`).
+    ///
+    /// To avoid saturation [`take`](`AsyncStats::take`) the statistics before the sum of samples reaches `u32::MAX`.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count != 0 && !self.is_saturated())
+            .then(|| f64::from(self.sum) / f64::from(self.count))
+    }
+}
+
+/// Streaming statistics accumulator.
+///
+/// Producers [`record`](`Self::record`) samples and the consumer waits for enough of them to be collected.
+/// Count and sum are updated together in a single atomic, so they are always consistent with each other.
+/// Min and max are stored in separate atomics and may include a sample which is not yet counted.
+///
+/// Available only on targets that support 64-bit atomics.
+#[derive(Debug)]
+pub struct AsyncStats {
+    totals: AsyncAtomic<Totals>,
+    min: AtomicU32,
+    max: AtomicU32,
+}
+
+impl Default for AsyncStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncStats {
+    pub fn new() -> Self {
+        Self {
+            totals: AsyncAtomic::new(Totals::default()),
+            min: AtomicU32::new(u32::MAX),
+            max: AtomicU32::new(0),
+        }
+    }
+
+    /// Underlying atomic count and sum, e.g. to subscribe to changes.
+    pub fn totals(&self) -> &AsyncAtomic<Totals> {
+        &self.totals
+    }
+
+    /// Record a sample.
+    pub fn record(&self, sample: u32) {
+        self.min.fetch_min(sample, Ordering::Relaxed);
+        self.max.fetch_max(sample, Ordering::Relaxed);
+        let _ = self.totals.fetch_update(|Totals { count, sum }| {
+            Some(Totals {
+                count: count.saturating_add(1),
+                sum: sum.saturating_add(sample),
+            })
+        });
+    }
+
+    /// Get current statistics.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let Totals { count, sum } = self.totals.load();
+        let (min, max) = (
+            self.min.load(Ordering::Relaxed),
+            self.max.load(Ordering::Relaxed),
+        );
+        Self::make_snapshot(count, sum, min, max)
+    }
+
+    /// Get current statistics and reset accumulator.
+    pub fn take(&self) -> StatsSnapshot {
+        let Totals { count, sum } = self.totals.swap(Totals::default());
+        let min = self.min.swap(u32::MAX, Ordering::Relaxed);
+        let max = self.max.swap(0, Ordering::Relaxed);
+        Self::make_snapshot(count, sum, min, max)
+    }
+
+    fn make_snapshot(count: u32, sum: u32, min: u32, max: u32) -> StatsSnapshot {
+        let nonempty = count != 0;
+        StatsSnapshot {
+            count,
+            sum,
+            min: nonempty.then_some(min),
+            max: nonempty.then_some(max),
+        }
+    }
+
+    /// Asynchronously wait for at least `count` samples to be recorded.
+    pub fn wait_count_at_least(
+        &self,
+        count: u32,
+    ) -> Wait<&AsyncAtomic<Totals>, impl FnMut(Totals) -> bool> {
+        self.totals.wait(move |x| x.count >= count)
+    }
+}
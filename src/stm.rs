@@ -0,0 +1,149 @@
+use crate::{AsyncAtomic, RawBits};
+use alloc::vec::Vec;
+use atomig::Atom;
+use core::{
+    hint,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Serializes commits of all transactions.
+static COMMIT_LOCK: AtomicBool = AtomicBool::new(false);
+
+struct CommitGuard;
+
+impl CommitGuard {
+    fn lock() -> Self {
+        while COMMIT_LOCK
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            hint::spin_loop();
+        }
+        Self
+    }
+}
+
+impl Drop for CommitGuard {
+    fn drop(&mut self) {
+        COMMIT_LOCK.store(false, Ordering::Release);
+    }
+}
+
+/// Atomic that can participate in [`Transaction`].
+trait Target {
+    fn generation(&self) -> usize;
+    /// Store raw bits without notifying subscribers.
+    fn store_bits(&self, bits: u64);
+    fn notify(&self);
+}
+
+impl<T: Atom<Repr: RawBits>> Target for AsyncAtomic<T> {
+    fn generation(&self) -> usize {
+        AsyncAtomic::generation(self)
+    }
+    fn store_bits(&self, bits: u64) {
+        self.value
+            .store(T::unpack(T::Repr::from_bits(bits)), Ordering::Release);
+    }
+    fn notify(&self) {
+        AsyncAtomic::notify(self)
+    }
+}
+
+fn same(a: &dyn Target, b: &dyn Target) -> bool {
+    core::ptr::addr_eq(a, b)
+}
+
+/// Set of reads and buffered writes of a [`transaction`].
+pub struct Transaction<'a> {
+    /// Read atomics and their generations at the moment of the first read.
+    reads: Vec<(&'a dyn Target, usize)>,
+    /// Written atomics and raw bits of values to be stored on commit.
+    writes: Vec<(&'a dyn Target, u64)>,
+}
+
+impl<'a> Transaction<'a> {
+    fn new() -> Self {
+        Self {
+            reads: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+
+    /// Read value of the atomic.
+    ///
+    /// Returns value written earlier in this transaction if any.
+    pub fn read<T: Atom<Repr: RawBits>>(&mut self, atomic: &'a AsyncAtomic<T>) -> T {
+        if let Some((_, bits)) = self.writes.iter().find(|(t, _)| same(*t, atomic)) {
+            return T::unpack(T::Repr::from_bits(*bits));
+        }
+        // Generation is taken before load, so a concurrent store is always detected on commit.
+        let generation = atomic.generation();
+        let value = atomic.load();
+        if !self.reads.iter().any(|(t, _)| same(*t, atomic)) {
+            self.reads.push((atomic, generation));
+        }
+        value
+    }
+
+    /// Buffer value to be stored into the atomic on commit.
+    pub fn write<T: Atom<Repr: RawBits>>(&mut self, atomic: &'a AsyncAtomic<T>, value: T) {
+        let bits = value.pack().to_bits();
+        match self.writes.iter_mut().find(|(t, _)| same(*t, atomic)) {
+            Some((_, old)) => *old = bits,
+            None => self.writes.push((atomic, bits)),
+        }
+    }
+
+    fn try_commit(&self) -> bool {
+        let _guard = CommitGuard::lock();
+        if self
+            .reads
+            .iter()
+            .any(|(target, generation)| target.generation() != *generation)
+        {
+            return false;
+        }
+        for (target, bits) in &self.writes {
+            target.store_bits(*bits);
+        }
+        // Subscribers are woken only after all values are stored.
+        for (target, _) in &self.writes {
+            target.notify();
+        }
+        true
+    }
+}
+
+/// Run `f` and atomically commit its writes, retrying if any of read atomics was modified meanwhile.
+///
+/// Conflicts are detected using [`generation`](`AsyncAtomic::generation`) counters.
+/// On commit all written values are stored and only then subscribers of the written atomics are woken, once per atomic.
+///
+/// `f` may be called several times and may observe inconsistent values in a run that is going to be retried,
+/// so it should not have side effects other than transaction reads and writes.
+/// Commits are atomic only with respect to other transactions,
+/// plain stores to the same atomics are not synchronized with them.
+///
+/// ```
+/// use async_atomic::{transaction, AsyncAtomic};
+///
+/// let (from, to) = (AsyncAtomic::new(10), AsyncAtomic::new(0));
+/// transaction(|tx| {
+///     let (balance, total) = (tx.read(&from), tx.read(&to));
+///     let amount = balance.min(3);
+///     tx.write(&from, balance - amount);
+///     tx.write(&to, total + amount);
+/// });
+/// assert_eq!((from.load(), to.load()), (7, 3));
+/// ```
+pub fn transaction<'a, R, F: FnMut(&mut Transaction<'a>) -> R>(mut f: F) -> R {
+    loop {
+        let mut tx = Transaction::new();
+        let ret = f(&mut tx);
+        if tx.try_commit() {
+            break ret;
+        }
+        hint::spin_loop();
+    }
+}
@@ -0,0 +1,556 @@
+use crate::AsyncAtomicRef;
+#[cfg(feature = "alloc")]
+use crate::{AsyncAtomic, Changed, Updates, Wait, WaitMap};
+#[cfg(feature = "alloc")]
+use alloc::sync::{Arc, Weak};
+#[cfg(feature = "alloc")]
+use atomig::{
+    impls::{PrimitiveAtomInteger, PrimitiveAtomLogic},
+    Atom, AtomInteger, AtomLogic,
+};
+#[cfg(feature = "alloc")]
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use core::{
+    ops::{Deref, DerefMut},
+    sync::atomic::Ordering,
+    task::Waker,
+};
+#[cfg(feature = "alloc")]
+use futures::{
+    future::FusedFuture,
+    stream::{FusedStream, Stream},
+};
+
+/// Subscriber that remembers the last seen [`generation`](`crate::AsyncAtomic::generation`) of the atomic.
+///
+/// Allows cheaply checking for updates without constructing a future, e.g. once per frame in a game loop.
+/// Subscriber dereferences to the original reference, so all [`AsyncAtomicRef`] methods are available too.
+///
+/// Several subscribers may check for updates independently, but they share the single waker of the atomic,
+/// so only the last polled one is woken. For fan-out to multiple waiting tasks use [`AsyncAtomicMulti::subscribe`](`crate::AsyncAtomicMulti::subscribe`).
+pub struct Subscriber<R: AsyncAtomicRef> {
+    pub inner: R,
+    pub seen: usize,
+}
+
+impl<R: AsyncAtomicRef> Subscriber<R> {
+    /// Create subscriber that treats current value as already seen.
+    pub fn new(inner: R) -> Self {
+        let seen = inner.as_atomic().generation();
+        Self { inner, seen }
+    }
+
+    /// Check whether the atomic was stored to since the last [`mark_seen`](`Self::mark_seen`) (or subscriber creation).
+    ///
+    /// Returns `true` even if the stored value is equal to the previous one.
+    pub fn has_changed(&self) -> bool {
+        self.inner.as_atomic().generation() != self.seen
+    }
+
+    /// Mark current value as seen.
+    pub fn mark_seen(&mut self) {
+        self.seen = self.inner.as_atomic().generation();
+    }
+}
+
+impl<R: AsyncAtomicRef> Deref for Subscriber<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/// [`Subscriber`] that is the only one claimed for the atomic.
+///
+/// Created by [`try_subscribe`](`AsyncAtomicRef::try_subscribe`), the claim is released on drop.
+pub struct ExclusiveSubscriber<R: AsyncAtomicRef> {
+    inner: Subscriber<R>,
+}
+
+impl<R: AsyncAtomicRef> ExclusiveSubscriber<R> {
+    /// Claim the atomic and create subscriber that treats current value as already seen.
+    ///
+    /// Returns `None` if the atomic is already claimed.
+    pub fn try_new(inner: R) -> Option<Self> {
+        inner
+            .as_atomic()
+            .exclusive
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        Some(Self {
+            inner: Subscriber::new(inner),
+        })
+    }
+}
+
+impl<R: AsyncAtomicRef> Deref for ExclusiveSubscriber<R> {
+    type Target = Subscriber<R>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef> DerefMut for ExclusiveSubscriber<R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef> Drop for ExclusiveSubscriber<R> {
+    fn drop(&mut self) {
+        self.inner
+            .as_atomic()
+            .exclusive
+            .store(false, Ordering::Release);
+    }
+}
+
+/// Temporary subscription that restores the previously registered waker when dropped.
+///
+/// Created by [`subscribe_scoped`](`AsyncAtomicRef::subscribe_scoped`).
+/// Allows a library to await the atomic for a while without permanently evicting the application's long-lived subscriber.
+/// If the atomic was stored to while the scope was active then the restored waker is woken,
+/// so the previous subscriber doesn't miss the update.
+pub struct ScopedSubscriber<R: AsyncAtomicRef> {
+    pub inner: R,
+    prev: Option<Waker>,
+    generation: usize,
+}
+
+impl<R: AsyncAtomicRef> ScopedSubscriber<R> {
+    /// Take previously registered waker and begin the scope.
+    pub fn new(inner: R) -> Self {
+        let atomic = inner.as_atomic();
+        let generation = atomic.generation();
+        let prev = atomic.take_waker();
+        Self {
+            inner,
+            prev,
+            generation,
+        }
+    }
+}
+
+impl<R: AsyncAtomicRef> Deref for ScopedSubscriber<R> {
+    type Target = R;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<R: AsyncAtomicRef> Drop for ScopedSubscriber<R> {
+    fn drop(&mut self) {
+        if let Some(waker) = self.prev.take() {
+            let atomic = self.inner.as_atomic();
+            // Panicking in drop is not an option, so if another task occupies the atomic
+            // the previous subscriber is woken to register by itself.
+            // Stores after registration wake the waker by themselves.
+            if atomic.try_register(&waker).is_err() || atomic.generation() != self.generation {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Error returned by [`WeakSubscriber`] when the atomic is dropped.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Dropped;
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Dropped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "atomic is dropped")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Dropped {}
+
+/// Subscriber that doesn't keep the atomic alive.
+///
+/// Waits resolve with [`Dropped`] error once all strong references to the atomic are dropped,
+/// so observers don't keep large state graphs alive.
+/// The atomic is upgraded only for the duration of a poll.
+#[cfg(feature = "alloc")]
+pub struct WeakSubscriber<T: Atom> {
+    pub inner: Weak<AsyncAtomic<T>>,
+    pub seen: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> WeakSubscriber<T> {
+    /// Create subscriber that treats current value as already seen.
+    pub fn new(atomic: &Arc<AsyncAtomic<T>>) -> Self {
+        Self {
+            inner: Arc::downgrade(atomic),
+            seen: atomic.generation(),
+        }
+    }
+
+    /// Get strong reference to the atomic if it is still alive.
+    pub fn upgrade(&self) -> Option<Arc<AsyncAtomic<T>>> {
+        self.inner.upgrade()
+    }
+
+    /// Check whether the atomic was stored to since the last seen generation.
+    pub fn has_changed(&self) -> Result<bool, Dropped> {
+        let atomic = self.upgrade().ok_or(Dropped)?;
+        Ok(atomic.generation() != self.seen)
+    }
+
+    /// Poll for predicate to be `true`.
+    pub fn poll_wait<F: FnMut(T) -> bool>(
+        &self,
+        cx: &mut Context<'_>,
+        mut pred: F,
+    ) -> Poll<Result<(), Dropped>> {
+        let atomic = match self.upgrade() {
+            Some(atomic) => atomic,
+            None => return Poll::Ready(Err(Dropped)),
+        };
+        atomic.register(cx.waker());
+        if pred(atomic.load()) {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Asynchronously wait for predicate to be `true`.
+    pub fn wait<F: FnMut(T) -> bool>(&self, pred: F) -> WeakWait<'_, T, F> {
+        WeakWait { inner: self, pred }
+    }
+
+    /// Asynchronously wait for the next store since the last seen generation and resolve with the stored value.
+    pub fn updated(&mut self) -> WeakUpdated<'_, T> {
+        WeakUpdated { inner: self }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> Clone for WeakSubscriber<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            seen: self.seen,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> fmt::Debug for WeakSubscriber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakSubscriber")
+            .field("seen", &self.seen)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Future to wait for predicate through [`WeakSubscriber`].
+#[cfg(feature = "alloc")]
+pub struct WeakWait<'a, T: Atom, F: FnMut(T) -> bool> {
+    pub inner: &'a WeakSubscriber<T>,
+    pub pred: F,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom, F: FnMut(T) -> bool> Unpin for WeakWait<'_, T, F> {}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom, F: FnMut(T) -> bool> Drop for WeakWait<'_, T, F> {
+    fn drop(&mut self) {
+        if let Some(atomic) = self.inner.upgrade() {
+            atomic.release_dropped();
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom, F: FnMut(T) -> bool> Future for WeakWait<'_, T, F> {
+    type Output = Result<(), Dropped>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.inner.poll_wait(cx, &mut this.pred)
+    }
+}
+
+/// Future to wait for the next store through [`WeakSubscriber`].
+#[cfg(feature = "alloc")]
+pub struct WeakUpdated<'a, T: Atom> {
+    pub inner: &'a mut WeakSubscriber<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> Unpin for WeakUpdated<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> Drop for WeakUpdated<'_, T> {
+    fn drop(&mut self) {
+        if let Some(atomic) = self.inner.upgrade() {
+            atomic.release_dropped();
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> Future for WeakUpdated<'_, T> {
+    type Output = Result<T, Dropped>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let atomic = match self.inner.upgrade() {
+            Some(atomic) => atomic,
+            None => return Poll::Ready(Err(Dropped)),
+        };
+        atomic.register(cx.waker());
+        let generation = atomic.generation();
+        if generation != self.inner.seen {
+            self.inner.seen = generation;
+            Poll::Ready(Ok(atomic.load()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// The only handle that can wait on the atomic created by [`AsyncAtomic::new_with_token`].
+///
+/// The token is not cloneable and all its wait methods borrow it mutably,
+/// the returned futures and streams don't expose the atomic,
+/// and the other handle, [`TokenProducer`], can't wait at all,
+/// so the type system guarantees that there is at most one waiting future at a time.
+#[cfg(feature = "alloc")]
+pub struct SubscriberToken<T: Atom> {
+    atomic: Arc<AsyncAtomic<T>>,
+}
+
+/// Writing handle of the atomic created by [`AsyncAtomic::new_with_token`].
+///
+/// It can be cloned to get multiple writers, but it doesn't provide any way to wait on the atomic.
+#[cfg(feature = "alloc")]
+pub struct TokenProducer<T: Atom> {
+    atomic: Arc<AsyncAtomic<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> AsyncAtomic<T> {
+    /// Create atomic split into a [`TokenProducer`] and the only [`SubscriberToken`] for it.
+    pub fn new_with_token(value: T) -> (TokenProducer<T>, SubscriberToken<T>) {
+        let atomic = Arc::new(Self::new(value));
+        let token = SubscriberToken {
+            atomic: atomic.clone(),
+        };
+        (TokenProducer { atomic }, token)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> TokenProducer<T> {
+    pub fn load(&self) -> T {
+        self.atomic.load()
+    }
+
+    pub fn store(&self, val: T) {
+        self.atomic.store(val);
+    }
+
+    pub fn swap(&self, val: T) -> T {
+        self.atomic.swap(val)
+    }
+
+    pub fn compare_exchange(&self, current: T, new: T) -> Result<T, T> {
+        self.atomic.compare_exchange(current, new)
+    }
+
+    pub fn fetch_update<F: FnMut(T) -> Option<T>>(&self, f: F) -> Result<T, T> {
+        self.atomic.fetch_update(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: AtomLogic> TokenProducer<T>
+where
+    T::Repr: PrimitiveAtomLogic,
+{
+    pub fn fetch_and(&self, val: T) -> T {
+        self.atomic.fetch_and(val)
+    }
+    pub fn fetch_or(&self, val: T) -> T {
+        self.atomic.fetch_or(val)
+    }
+    pub fn fetch_xor(&self, val: T) -> T {
+        self.atomic.fetch_xor(val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: AtomInteger> TokenProducer<T>
+where
+    T::Repr: PrimitiveAtomInteger,
+{
+    pub fn fetch_add(&self, val: T) -> T {
+        self.atomic.fetch_add(val)
+    }
+    pub fn fetch_sub(&self, val: T) -> T {
+        self.atomic.fetch_sub(val)
+    }
+    pub fn fetch_max(&self, val: T) -> T {
+        self.atomic.fetch_max(val)
+    }
+    pub fn fetch_min(&self, val: T) -> T {
+        self.atomic.fetch_min(val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> Clone for TokenProducer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            atomic: self.atomic.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom + fmt::Debug> fmt::Debug for TokenProducer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TokenProducer").field(&self.load()).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> SubscriberToken<T> {
+    pub fn load(&self) -> T {
+        self.atomic.load()
+    }
+
+    /// Asynchronously wait for predicate to be `true`.
+    pub fn wait<F: FnMut(T) -> bool>(&mut self, pred: F) -> TokenWait<'_, T, F> {
+        TokenWait {
+            inner: (*self.atomic).wait(pred),
+        }
+    }
+
+    /// Asynchronously wait until `map` returned `Some(x)` and then resolve with `x`.
+    pub fn wait_map<U, F: FnMut(T) -> Option<U>>(&mut self, map: F) -> TokenWaitMap<'_, T, F> {
+        TokenWaitMap {
+            inner: (*self.atomic).wait_map(map),
+        }
+    }
+
+    /// Stream that yields value when it is changed, starting from the current one.
+    pub fn changed(&mut self) -> TokenChanged<'_, T>
+    where
+        T: PartialEq + Clone,
+    {
+        TokenChanged {
+            inner: (&*self.atomic).changed(),
+        }
+    }
+
+    /// Stream that yields value on every store.
+    pub fn updates(&mut self) -> TokenUpdates<'_, T> {
+        TokenUpdates {
+            inner: (&*self.atomic).updates(),
+        }
+    }
+}
+
+/// Future returned by [`SubscriberToken::wait`].
+///
+/// Unlike [`Wait`] it doesn't expose the atomic, so no other waiting future can be made from it.
+#[cfg(feature = "alloc")]
+pub struct TokenWait<'a, T: Atom, F: FnMut(T) -> bool> {
+    inner: Wait<&'a AsyncAtomic<T>, F>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom, F: FnMut(T) -> bool> Future for TokenWait<'_, T, F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        Pin::new(&mut self.inner).poll(cx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom, F: FnMut(T) -> bool> FusedFuture for TokenWait<'_, T, F> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+/// Future returned by [`SubscriberToken::wait_map`].
+#[cfg(feature = "alloc")]
+pub struct TokenWaitMap<'a, T: Atom, F> {
+    inner: WaitMap<&'a AsyncAtomic<T>, F>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom, U, F: FnMut(T) -> Option<U>> Future for TokenWaitMap<'_, T, F> {
+    type Output = U;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<U> {
+        Pin::new(&mut self.inner).poll(cx)
+    }
+}
+
+/// Stream returned by [`SubscriberToken::changed`].
+#[cfg(feature = "alloc")]
+pub struct TokenChanged<'a, T: Atom + PartialEq + Clone> {
+    inner: Changed<&'a AsyncAtomic<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom + PartialEq + Clone> Stream for TokenChanged<'_, T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom + PartialEq + Clone> FusedStream for TokenChanged<'_, T> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Stream returned by [`SubscriberToken::updates`].
+#[cfg(feature = "alloc")]
+pub struct TokenUpdates<'a, T: Atom> {
+    inner: Updates<&'a AsyncAtomic<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> Stream for TokenUpdates<'_, T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom> FusedStream for TokenUpdates<'_, T> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Atom + fmt::Debug> fmt::Debug for SubscriberToken<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SubscriberToken")
+            .field(&self.load())
+            .finish()
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, Wait, WaitMap};
+use atomig::Atom;
+use core::mem::align_of;
+
+/// Value packed together with a wrap-around sequence number (tag) into a single atomic.
+///
+/// Allows consumers to distinguish storing the same value again from no store at all,
+/// e.g. [`changed`](`crate::AsyncAtomicRef::changed`) yields every [`store_next`](`AsyncAtomic::store_next`).
+///
+/// Available only on targets that support 64-bit atomics.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(target_has_atomic = "64")]
+pub struct Tagged<T> {
+    pub value: T,
+    pub tag: u32,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T> Tagged<T> {
+    pub fn new(value: T, tag: u32) -> Self {
+        Self { value, tag }
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: Atom<Repr = u32>> Atom for Tagged<T> {
+    type Repr = u64;
+
+    fn pack(self) -> u64 {
+        (u64::from(self.tag) << 32) | u64::from(self.value.pack())
+    }
+    fn unpack(src: u64) -> Self {
+        Self {
+            value: T::unpack(src as u32),
+            tag: (src >> 32) as u32,
+        }
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: Atom<Repr = u32>> AsyncAtomic<Tagged<T>> {
+    /// Store value with the tag incremented by one and return the new tag.
+    pub fn store_next(&self, value: T) -> u32 {
+        let repr = value.pack();
+        let (Ok(old) | Err(old)) =
+            self.fetch_update(|old| Some(Tagged::new(T::unpack(repr), old.tag.wrapping_add(1))));
+        old.tag.wrapping_add(1)
+    }
+}
+
+/// Value packed together with a user-supplied `u32` timestamp or sequence number into a single atomic.
+///
+/// Stamps are compared using serial number arithmetic, so they may wrap around
+/// as long as compared stamps are less than `2^31` apart.
+///
+/// Available only on targets that support 64-bit atomics.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(target_has_atomic = "64")]
+pub struct Stamped<T> {
+    pub value: T,
+    pub stamp: u32,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T> Stamped<T> {
+    pub fn new(value: T, stamp: u32) -> Self {
+        Self { value, stamp }
+    }
+
+    /// Whether the stamp is newer than `stamp`.
+    pub fn is_newer_than(&self, stamp: u32) -> bool {
+        (self.stamp.wrapping_sub(stamp) as i32) > 0
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: Atom<Repr = u32>> Atom for Stamped<T> {
+    type Repr = u64;
+
+    fn pack(self) -> u64 {
+        Tagged::new(self.value, self.stamp).pack()
+    }
+    fn unpack(src: u64) -> Self {
+        let Tagged { value, tag } = Tagged::unpack(src);
+        Self::new(value, tag)
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: Atom<Repr = u32>> AsyncAtomic<Stamped<T>> {
+    /// Asynchronously wait for a value stamped newer than `stamp` and resolve with it.
+    pub fn wait_newer_than(
+        &self,
+        stamp: u32,
+    ) -> WaitMap<&Self, impl FnMut(Stamped<T>) -> Option<Stamped<T>>> {
+        self.wait_map(move |x| x.is_newer_than(stamp).then_some(x))
+    }
+}
+
+/// Pointer packed together with a `BITS`-wide wrap-around tag into a single pointer-sized atomic.
+///
+/// The tag is stored in the low bits of the pointer that are always zero due to alignment,
+/// so `T` must be aligned to at least `1 << BITS` bytes, otherwise packing fails to compile.
+/// Incrementing the tag on every store mitigates ABA problem in compare-exchange loops.
+#[derive(Debug)]
+#[cfg(target_has_atomic = "ptr")]
+pub struct TaggedPtr<T, const BITS: u32> {
+    pub ptr: *mut T,
+    pub tag: usize,
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BITS: u32> Clone for TaggedPtr<T, BITS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BITS: u32> Copy for TaggedPtr<T, BITS> {}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BITS: u32> PartialEq for TaggedPtr<T, BITS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr && self.tag == other.tag
+    }
+}
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BITS: u32> Eq for TaggedPtr<T, BITS> {}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BITS: u32> TaggedPtr<T, BITS> {
+    /// Mask of tag bits.
+    pub const MASK: usize = (1 << BITS) - 1;
+    const ALIGNED: () = assert!(
+        align_of::<T>() > Self::MASK,
+        "alignment of `T` is too small to fit the tag"
+    );
+
+    /// Create tagged pointer. Tag is truncated to `BITS`.
+    pub fn new(ptr: *mut T, tag: usize) -> Self {
+        Self {
+            ptr,
+            tag: tag & Self::MASK,
+        }
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BITS: u32> Atom for TaggedPtr<T, BITS> {
+    type Repr = usize;
+
+    fn pack(self) -> usize {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ALIGNED;
+        debug_assert_eq!(self.ptr as usize & Self::MASK, 0, "pointer is misaligned");
+        self.ptr as usize | (self.tag & Self::MASK)
+    }
+    fn unpack(src: usize) -> Self {
+        Self::new((src & !Self::MASK) as *mut T, src & Self::MASK)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, const BITS: u32> AsyncAtomic<TaggedPtr<T, BITS>> {
+    /// Store pointer with the tag incremented by one and return the new tag.
+    pub fn store_next(&self, ptr: *mut T) -> usize {
+        let (Ok(old) | Err(old)) =
+            self.fetch_update(|old| Some(TaggedPtr::new(ptr, old.tag.wrapping_add(1))));
+        old.tag.wrapping_add(1) & TaggedPtr::<T, BITS>::MASK
+    }
+
+    /// Asynchronously wait for pointer to become different from `ptr` regardless of tag.
+    pub fn wait_ptr_changed(
+        &self,
+        ptr: *mut T,
+    ) -> Wait<&Self, impl FnMut(TaggedPtr<T, BITS>) -> bool> {
+        // Compare addresses to keep the future `Send`.
+        let addr = ptr as usize;
+        self.wait(move |x| x.ptr as usize != addr)
+    }
+
+    /// Asynchronously wait for tag to become different from `tag` regardless of pointer.
+    pub fn wait_tag_changed(
+        &self,
+        tag: usize,
+    ) -> Wait<&Self, impl FnMut(TaggedPtr<T, BITS>) -> bool> {
+        let tag = tag & TaggedPtr::<T, BITS>::MASK;
+        self.wait(move |x| x.tag != tag)
+    }
+}
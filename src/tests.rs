@@ -1,14 +1,23 @@
 extern crate std;
 
-use crate::{prelude::*, AsyncAtomic};
+use crate::{
+    prelude::*, AsyncAtomic, AsyncAtomicMulti, AsyncAtomicPtr, AsyncAtomicU64, Broadcast,
+    CancelToken, Cancelled, Doorbell, Elapsed, Handshake, Lagged, Lanes, LocalAsyncAtomic, Mailbox,
+    Stamped, Tagged, TaggedPtr,
+};
 use async_std::{
     future::timeout,
     task::{sleep, spawn},
     test as async_test,
 };
-use core::sync::atomic::AtomicUsize;
-use futures::stream::StreamExt;
-use std::{sync::Arc, time::Duration, vec::Vec};
+use atomig::Atom;
+use core::{
+    future::{poll_fn, IntoFuture},
+    sync::atomic::AtomicUsize,
+    task::Poll,
+};
+use futures::stream::{self, StreamExt};
+use std::{boxed::Box, ptr, sync::Arc, time::Duration, vec::Vec};
 
 const SMALL_TIMEOUT: Duration = Duration::from_millis(10);
 const BIG_TIMEOUT: Duration = Duration::from_millis(1000);
@@ -123,6 +132,37 @@ async fn static_() {
     assert_eq!(v, Some(1));
 }
 
+#[async_test]
+async fn static_const_new() {
+    static ATOMIC: AsyncAtomicU64 = AsyncAtomicU64::const_new(0);
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        ATOMIC.store(1);
+    });
+
+    timeout(BIG_TIMEOUT, ATOMIC.wait(|x| x == 1)).await.unwrap();
+}
+
+#[async_test]
+async fn static_macro_accessors() {
+    crate::async_atomic! {
+        static FRAMES: u64 = 0;
+    }
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        FRAMES::store(1);
+    });
+
+    timeout(BIG_TIMEOUT, FRAMES::wait(|x| x == 1))
+        .await
+        .unwrap();
+    assert_eq!(FRAMES::swap(2), 1);
+    assert_eq!(FRAMES::load(), 2);
+    assert!(ptr::eq(FRAMES::atomic(), &FRAMES));
+}
+
 #[async_test]
 async fn stream() {
     const COUNT: usize = 64;
@@ -145,3 +185,2099 @@ async fn stream() {
     })
     .await
 }
+
+#[async_test]
+async fn changed_initial() {
+    let atomic = AsyncAtomic::<usize>::new(5);
+    let mut stream = (&atomic).changed();
+
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some(5)
+    );
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+
+    atomic.store(6);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some(6)
+    );
+}
+
+#[async_test]
+async fn updates() {
+    let atomic = AsyncAtomic::<usize>::new(1);
+    let mut stream = (&atomic).updates();
+
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+
+    atomic.store(1);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some(1)
+    );
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+
+    atomic.fetch_add(1);
+    atomic.fetch_add(1);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some(3)
+    );
+    assert_eq!(atomic.generation(), 3);
+}
+
+#[async_test]
+async fn changed_by() {
+    let atomic = AsyncAtomic::<u64>::new(1 << 32);
+    let mut stream = (&atomic).changed_by(|a, b| a >> 32 == b >> 32);
+
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some(1 << 32)
+    );
+
+    atomic.fetch_add(1);
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+
+    atomic.fetch_add(1 << 32);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some((2 << 32) + 1)
+    );
+}
+
+#[async_test]
+async fn mapped() {
+    let atomic = AsyncAtomic::<u8>::new(0);
+    let view = (&atomic).mapped(|x| x >= 10);
+    assert!(!view.load());
+
+    atomic.store(5);
+    assert!(timeout(SMALL_TIMEOUT, view.wait(|x| x)).await.is_err());
+    atomic.store(10);
+    timeout(SMALL_TIMEOUT, view.wait(|x| x)).await.unwrap();
+
+    let mut stream = view.changed();
+    assert_eq!(stream.next().await, Some(true));
+    atomic.store(11);
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+    atomic.store(3);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some(false)
+    );
+}
+
+#[async_test]
+async fn transitions() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut stream = (&atomic).transitions();
+
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+
+    atomic.store(1);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some((0, 1))
+    );
+    atomic.store(1);
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+    atomic.store(2);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some((1, 2))
+    );
+}
+
+#[test]
+fn has_changed() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut sub = (&atomic).subscribe();
+    assert!(!sub.has_changed());
+
+    atomic.store(0);
+    assert!(sub.has_changed());
+    sub.mark_seen();
+    assert!(!sub.has_changed());
+
+    atomic.fetch_add(1);
+    assert!(sub.has_changed());
+    assert_eq!(sub.load(), 1);
+}
+
+#[async_test]
+async fn with_sequence() {
+    let atomic = AsyncAtomic::<usize>::new(10);
+    let mut stream = (&atomic).changed().with_sequence();
+
+    assert_eq!(stream.next().await, Some((0, 10)));
+    atomic.store(20);
+    assert_eq!(stream.next().await, Some((1, 20)));
+    atomic.store(30);
+    assert_eq!(stream.next().await, Some((2, 30)));
+}
+
+#[async_test]
+async fn poll_changed() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut prev = None;
+
+    assert_eq!(poll_fn(|cx| atomic.poll_changed(cx, &mut prev)).await, 0);
+    assert!(timeout(
+        SMALL_TIMEOUT,
+        poll_fn(|cx| atomic.poll_changed(cx, &mut prev))
+    )
+    .await
+    .is_err());
+
+    atomic.store(1);
+    assert_eq!(poll_fn(|cx| atomic.poll_changed(cx, &mut prev)).await, 1);
+    poll_fn(|cx| atomic.poll_wait(cx, |x| x == 1)).await;
+}
+
+#[async_test]
+async fn await_update() {
+    let sub = Arc::new(AsyncAtomic::<usize>::new(0));
+    let val = sub.clone();
+
+    assert!(timeout(SMALL_TIMEOUT, sub.as_ref().into_future())
+        .await
+        .is_err());
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        val.store(0);
+    });
+
+    assert_eq!(timeout(BIG_TIMEOUT, async { (&*sub).await }).await, Ok(0));
+}
+
+#[async_test]
+async fn wait_while() {
+    let atomic = AsyncAtomic::<bool>::new(true);
+
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait_while(|busy| busy))
+        .await
+        .is_err());
+
+    atomic.store(false);
+    timeout(SMALL_TIMEOUT, atomic.wait_while(|busy| busy))
+        .await
+        .unwrap();
+}
+
+#[async_test]
+async fn wait_map() {
+    let atomic = AsyncAtomic::<u32>::new(0);
+    let digit = |x| char::from_digit(x, 10);
+
+    atomic.store(42);
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait_map(digit))
+        .await
+        .is_err());
+
+    atomic.store(7);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, atomic.wait_map(digit)).await,
+        Ok('7')
+    );
+}
+
+#[async_test]
+async fn wait_and_swap() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait_and_swap(|x| x > 0, 0))
+        .await
+        .is_err());
+
+    atomic.store(3);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, atomic.wait_and_swap(|x| x > 0, 0)).await,
+        Ok(3)
+    );
+    assert_eq!(atomic.load(), 0);
+}
+
+#[async_test]
+async fn wait_and_take() {
+    let sub = Arc::new(AsyncAtomic::<usize>::new(0));
+    let val = sub.clone();
+
+    assert!(timeout(SMALL_TIMEOUT, sub.wait_and_take()).await.is_err());
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        val.store(5);
+    });
+
+    assert_eq!(timeout(BIG_TIMEOUT, sub.wait_and_take()).await, Ok(5));
+    assert_eq!(sub.load(), 0);
+}
+
+#[async_test]
+async fn wait_compare_exchange() {
+    let sub = Arc::new(AsyncAtomic::<usize>::new(0));
+    let val = sub.clone();
+
+    assert!(timeout(SMALL_TIMEOUT, sub.wait_compare_exchange(1, 2))
+        .await
+        .is_err());
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        val.store(3);
+        sleep(SMALL_TIMEOUT).await;
+        val.store(1);
+    });
+
+    timeout(BIG_TIMEOUT, sub.wait_compare_exchange(1, 2))
+        .await
+        .unwrap();
+    assert_eq!(sub.load(), 2);
+}
+
+#[async_test]
+async fn handshake() {
+    let client = Arc::new(Handshake::<u32, u32>::new(0, 0));
+    let server = client.clone();
+
+    spawn(async move {
+        loop {
+            server.serve(|x| 2 * x).await;
+        }
+    });
+
+    for x in [1, 1, 2, 0] {
+        assert_eq!(timeout(BIG_TIMEOUT, client.call(x)).await, Ok(2 * x));
+    }
+}
+
+#[async_test]
+async fn mailbox() {
+    let consumer = Arc::new(Mailbox::<u32>::new(0));
+    let producer = consumer.clone();
+
+    assert_eq!(consumer.try_take(), None);
+    assert!(timeout(SMALL_TIMEOUT, consumer.take_new()).await.is_err());
+
+    producer.post(1);
+    producer.post(2);
+    assert_eq!(consumer.take_new().await, 2);
+    assert_eq!(consumer.try_take(), None);
+    assert_eq!(consumer.load(), 2);
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        producer.post(2);
+    });
+    assert_eq!(timeout(BIG_TIMEOUT, consumer.take_new()).await, Ok(2));
+}
+
+#[async_test]
+async fn bits() {
+    let sub = Arc::new(AsyncAtomic::<u32>::new(0));
+    let val = sub.clone();
+
+    assert!(timeout(SMALL_TIMEOUT, sub.wait_bit_set(3)).await.is_err());
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        assert!(!val.set_bit(3));
+    });
+
+    timeout(BIG_TIMEOUT, sub.wait_bit_set(3)).await.unwrap();
+    assert!(sub.get_bit(3));
+    assert_eq!(sub.load(), 0b1000);
+
+    assert!(sub.clear_bit(3));
+    timeout(SMALL_TIMEOUT, sub.wait_bit_clear(3)).await.unwrap();
+}
+
+#[async_test]
+async fn changed_masked() {
+    const MASK: u32 = 0xf0;
+    let atomic = AsyncAtomic::<u32>::new(0);
+    let mut stream = (&atomic).changed_by(|a, b| a & MASK == b & MASK);
+
+    assert_eq!(stream.next().await, Some(0));
+    atomic.fetch_or(0x0f);
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+    atomic.fetch_or(0x10);
+    assert_eq!(stream.next().await, Some(0x1f));
+}
+
+#[async_test]
+async fn lanes() {
+    let sub = Arc::new(Lanes::<4>::new([1, 2, 3, 4]));
+    let val = sub.clone();
+    assert_eq!(Lanes::<4>::BITS, 16);
+
+    assert_eq!(sub.fetch_sub(0, 2), 1);
+    assert_eq!(sub.load_all(), [0xffff, 2, 3, 4]);
+    assert_eq!(sub.fetch_add(0, 1), 0xffff);
+    assert_eq!(sub.load_all(), [0, 2, 3, 4]);
+
+    assert!(timeout(SMALL_TIMEOUT, sub.wait(2, |x| x == 0))
+        .await
+        .is_err());
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        val.store(1, 0);
+        sleep(SMALL_TIMEOUT).await;
+        val.store(2, 0);
+    });
+
+    timeout(BIG_TIMEOUT, sub.wait(2, |x| x == 0)).await.unwrap();
+    assert_eq!(sub.load_all(), [0, 0, 0, 4]);
+}
+
+#[async_test]
+async fn tagged() {
+    let atomic = AsyncAtomic::new(Tagged::new(1u32, 0));
+    let mut stream = (&atomic).changed().map(|x| x.value);
+
+    assert_eq!(stream.next().await, Some(1));
+    assert_eq!(atomic.store_next(1), 1);
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, stream.next()).await.unwrap(),
+        Some(1)
+    );
+    assert_eq!(atomic.load(), Tagged::new(1, 1));
+
+    atomic.store(Tagged::new(2, u32::MAX));
+    assert_eq!(atomic.store_next(3), 0);
+    assert_eq!(atomic.load(), Tagged::new(3, 0));
+}
+
+#[async_test]
+async fn tagged_ptr() {
+    let mut a = 1u64;
+    let mut b = 2u64;
+    let (pa, pb) = (&mut a as *mut u64, &mut b as *mut u64);
+    let atomic = AsyncAtomic::new(TaggedPtr::<u64, 3>::new(pa, 7));
+    assert_eq!(atomic.load(), TaggedPtr::new(pa, 7));
+
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait_tag_changed(7))
+        .await
+        .is_err());
+    assert_eq!(atomic.store_next(pa), 0);
+    timeout(SMALL_TIMEOUT, atomic.wait_tag_changed(7))
+        .await
+        .unwrap();
+
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait_ptr_changed(pa))
+        .await
+        .is_err());
+    assert_eq!(atomic.store_next(pb), 1);
+    timeout(SMALL_TIMEOUT, atomic.wait_ptr_changed(pa))
+        .await
+        .unwrap();
+    assert_eq!(atomic.load(), TaggedPtr::new(pb, 1));
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum State {
+    Idle,
+    Running,
+    Done,
+}
+
+impl Atom for State {
+    type Repr = u8;
+    fn pack(self) -> u8 {
+        self as u8
+    }
+    fn unpack(src: u8) -> Self {
+        match src {
+            0 => State::Idle,
+            1 => State::Running,
+            2 => State::Done,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[async_test]
+async fn state_machine() {
+    let sub = Arc::new(AsyncAtomic::new(State::Idle));
+    let val = sub.clone();
+    let mut transitions = sub.clone().transitions();
+
+    assert!(timeout(SMALL_TIMEOUT, sub.wait_state(State::Done))
+        .await
+        .is_err());
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        assert_eq!(val.transition(State::Idle, State::Running), Ok(()));
+        assert_eq!(
+            val.transition(State::Idle, State::Done),
+            Err(State::Running)
+        );
+        assert_eq!(val.transition(State::Running, State::Done), Ok(()));
+    });
+
+    timeout(BIG_TIMEOUT, sub.wait_state(State::Done))
+        .await
+        .unwrap();
+    assert_eq!(transitions.next().await, Some((State::Idle, State::Done)));
+}
+
+#[cfg(all(feature = "std", unix))]
+#[test]
+fn fd_notifier() {
+    use crate::FdNotifier;
+    use std::{
+        io::{ErrorKind, Read},
+        os::unix::{io::AsFd, net::UnixStream},
+    };
+
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let notifier = FdNotifier::new(&atomic).unwrap();
+    let mut fd = UnixStream::from(notifier.as_fd().try_clone_to_owned().unwrap());
+    let mut buf = [0; 16];
+
+    assert_eq!(fd.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    atomic.store(1);
+    assert!(fd.read(&mut buf).unwrap() > 0);
+
+    notifier.reset().unwrap();
+    assert_eq!(notifier.load(), 1);
+    assert_eq!(fd.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    atomic.store(2);
+    assert!(fd.read(&mut buf).unwrap() > 0);
+}
+
+#[cfg(all(feature = "std", unix))]
+#[test]
+fn fd_notifier_release() {
+    use crate::{FdNotifier, WakerPolicy};
+    use futures::task::noop_waker;
+
+    let atomic = AsyncAtomic::<usize>::new(0).with_waker_policy(WakerPolicy::Reject);
+    let notifier = FdNotifier::new(&atomic).unwrap();
+    assert!(atomic.has_waiter());
+    // Dropped notifier doesn't occupy the atomic anymore.
+    drop(notifier);
+    assert!(!atomic.has_waiter());
+    assert_eq!(atomic.try_register(&noop_waker()), Ok(()));
+}
+
+#[cfg(all(feature = "mio", unix))]
+#[test]
+fn fd_notifier_mio() {
+    use crate::FdNotifier;
+    use mio::{Events, Interest, Poll, Token};
+
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut notifier = FdNotifier::new(&atomic).unwrap();
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+    poll.registry()
+        .register(&mut notifier, Token(0), Interest::READABLE)
+        .unwrap();
+
+    poll.poll(&mut events, Some(SMALL_TIMEOUT)).unwrap();
+    assert!(events.is_empty());
+
+    atomic.store(1);
+    poll.poll(&mut events, Some(BIG_TIMEOUT)).unwrap();
+    assert!(events
+        .iter()
+        .any(|e| e.token() == Token(0) && e.is_readable()));
+}
+
+#[cfg(feature = "tokio")]
+#[async_test]
+async fn watch_interop() {
+    use crate::{forward_from_watch, forward_to_watch};
+    use tokio::sync::watch;
+
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0));
+
+    let (tx, rx) = watch::channel(1);
+    let forward = spawn(forward_from_watch(rx, atomic.clone()));
+    timeout(BIG_TIMEOUT, atomic.wait(|x| x == 1)).await.unwrap();
+    tx.send(2).unwrap();
+    timeout(BIG_TIMEOUT, atomic.wait(|x| x == 2)).await.unwrap();
+    drop(tx);
+    timeout(BIG_TIMEOUT, forward).await.unwrap();
+
+    let (tx, mut rx) = watch::channel(0);
+    spawn(forward_to_watch(atomic.clone(), tx));
+    timeout(BIG_TIMEOUT, rx.wait_for(|x| *x == 2))
+        .await
+        .unwrap()
+        .unwrap();
+    atomic.store(3);
+    timeout(BIG_TIMEOUT, rx.wait_for(|x| *x == 3))
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[cfg(feature = "futures-signals")]
+#[async_test]
+async fn signal_interop() {
+    use crate::forward_from_signal;
+    use futures_signals::signal::{Mutable, SignalExt};
+
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0));
+    let mutable = Mutable::new(1);
+    spawn(forward_from_signal(mutable.signal(), atomic.clone()));
+    timeout(BIG_TIMEOUT, atomic.wait(|x| x == 1)).await.unwrap();
+
+    let mut values = atomic.clone().changed().to_stream();
+    assert_eq!(timeout(BIG_TIMEOUT, values.next()).await, Ok(Some(1)));
+    mutable.set(2);
+    assert_eq!(timeout(BIG_TIMEOUT, values.next()).await, Ok(Some(2)));
+}
+
+#[async_test]
+async fn sink() {
+    const COUNT: usize = 8;
+    let sub = Arc::new(AsyncAtomic::<usize>::new(0));
+    let val = sub.clone();
+
+    spawn(async move {
+        stream::iter(1..=COUNT)
+            .then(|x| async move {
+                sleep(SMALL_TIMEOUT).await;
+                Ok(x)
+            })
+            .forward(&*val)
+            .await
+            .unwrap();
+    });
+
+    let data = timeout(
+        BIG_TIMEOUT,
+        sub.clone().changed().take(COUNT + 1).collect::<Vec<_>>(),
+    )
+    .await
+    .unwrap();
+    assert!(data.into_iter().eq(0..=COUNT));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserialize() {
+    use serde::{
+        de::{
+            value::{Error, U32Deserializer},
+            IntoDeserializer,
+        },
+        Deserialize,
+    };
+
+    let de: U32Deserializer<Error> = 5u32.into_deserializer();
+    let atomic = AsyncAtomic::<u32>::deserialize(de).unwrap();
+    assert_eq!(atomic.load(), 5);
+}
+
+#[test]
+fn exclusive_access() {
+    let mut atomic = AsyncAtomic::<usize>::new(1);
+    assert_eq!(
+        atomic.with_mut(|x| {
+            *x += 1;
+            *x
+        }),
+        2
+    );
+    assert_eq!(atomic.generation(), 1);
+    assert_eq!(atomic.into_inner(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn try_unwrap() {
+    let arc = Arc::new(AsyncAtomic::<usize>::new(3));
+    let other = arc.clone();
+    let arc = AsyncAtomic::try_unwrap(arc).unwrap_err();
+    drop(other);
+    assert_eq!(AsyncAtomic::try_unwrap(arc).ok(), Some(3));
+}
+
+#[async_test]
+async fn local() {
+    let atomic = LocalAsyncAtomic::<usize>::new(0);
+
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait(|x| x > 0))
+        .await
+        .is_err());
+    atomic.store(1);
+    timeout(SMALL_TIMEOUT, atomic.wait(|x| x > 0))
+        .await
+        .unwrap();
+    assert_eq!(atomic.generation(), 1);
+
+    let mut stream = atomic.changed();
+    assert_eq!(stream.next().await, Some(1));
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+    atomic.store(2);
+    assert_eq!(stream.next().await, Some(2));
+
+    assert_eq!(
+        atomic
+            .wait_and_update(|x| if x == 2 { Some(3) } else { None })
+            .await,
+        2
+    );
+    assert_eq!(atomic.into_inner(), 3);
+}
+
+#[async_test]
+async fn pointer_publication() {
+    let slot = Arc::new(AsyncAtomicPtr::<usize>::const_new(ptr::null_mut()));
+    let producer = slot.clone();
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        producer.store(Box::into_raw(Box::new(42)));
+    });
+
+    let ptr = timeout(BIG_TIMEOUT, slot.wait_map(|p| (!p.is_null()).then_some(p)))
+        .await
+        .unwrap();
+    let value = unsafe { Box::from_raw(ptr) };
+    assert_eq!(*value, 42);
+}
+
+#[async_test]
+async fn doorbell() {
+    static BELL: Doorbell = Doorbell::new();
+
+    assert!(timeout(SMALL_TIMEOUT, BELL.wait()).await.is_err());
+    BELL.ring();
+    BELL.ring();
+    assert_eq!(BELL.pending(), 2);
+    assert_eq!(timeout(SMALL_TIMEOUT, BELL.wait()).await, Ok(2));
+    assert_eq!(BELL.take(), 0);
+
+    spawn(async {
+        sleep(SMALL_TIMEOUT).await;
+        BELL.ring();
+    });
+    assert_eq!(timeout(BIG_TIMEOUT, BELL.wait()).await, Ok(1));
+}
+
+#[async_test]
+async fn wait_timeout() {
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0));
+    assert_eq!(
+        atomic.wait_timeout(|x| x > 0, &sleep, SMALL_TIMEOUT).await,
+        Err(Elapsed)
+    );
+
+    let other = atomic.clone();
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        other.store(1);
+    });
+    assert_eq!(
+        atomic.wait_timeout(|x| x > 0, &sleep, BIG_TIMEOUT).await,
+        Ok(())
+    );
+}
+
+#[cfg(feature = "embassy-time")]
+#[async_test]
+async fn wait_timeout_embassy() {
+    use crate::EmbassyTimer;
+
+    let atomic = AsyncAtomic::<usize>::new(0);
+    assert_eq!(
+        atomic
+            .wait_timeout(|x| x > 0, &EmbassyTimer, SMALL_TIMEOUT)
+            .await,
+        Err(Elapsed)
+    );
+    atomic.store(1);
+    assert_eq!(
+        atomic
+            .wait_timeout(|x| x > 0, &EmbassyTimer, BIG_TIMEOUT)
+            .await,
+        Ok(())
+    );
+}
+
+#[async_test]
+async fn wait_with_cancel() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let token = Arc::new(CancelToken::new());
+
+    let other = token.clone();
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        other.cancel();
+    });
+    assert_eq!(
+        timeout(BIG_TIMEOUT, atomic.wait_with_cancel(|x| x > 0, &*token))
+            .await
+            .unwrap(),
+        Err(Cancelled)
+    );
+    assert!(token.is_cancelled());
+
+    atomic.store(1);
+    assert_eq!(
+        atomic.wait_with_cancel(|x| x > 0, sleep(BIG_TIMEOUT)).await,
+        Ok(())
+    );
+}
+
+#[async_test]
+async fn subscribe_scoped() {
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0));
+    let app = spawn({
+        let atomic = atomic.clone();
+        async move { atomic.wait(|x| x == 2).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+
+    {
+        let scoped = (&*atomic).subscribe_scoped();
+        let other = atomic.clone();
+        spawn(async move {
+            sleep(SMALL_TIMEOUT).await;
+            other.store(1);
+        });
+        timeout(BIG_TIMEOUT, scoped.wait(|x| x == 1)).await.unwrap();
+    }
+
+    atomic.store(2);
+    timeout(BIG_TIMEOUT, app).await.unwrap();
+}
+
+#[async_test]
+async fn subscribe_scoped_reject() {
+    use crate::WakerPolicy;
+
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0).with_waker_policy(WakerPolicy::Reject));
+    let app = spawn({
+        let atomic = atomic.clone();
+        async move { atomic.wait(|x| x == 2).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+
+    {
+        // Scope takes the place of the waiting task, so its wait is not rejected.
+        let scoped = (&*atomic).subscribe_scoped();
+        assert!(!atomic.has_waiter());
+        atomic.store(1);
+        timeout(BIG_TIMEOUT, scoped.wait(|x| x == 1)).await.unwrap();
+    }
+
+    atomic.store(2);
+    timeout(BIG_TIMEOUT, app).await.unwrap();
+}
+
+#[async_test]
+async fn multi() {
+    static ATOMIC: AsyncAtomicMulti<usize, 2> = AsyncAtomicMulti::<usize, 2>::const_new(0);
+    let first = ATOMIC.subscribe();
+    let second = ATOMIC.subscribe();
+    assert!(ATOMIC.try_subscribe().is_none());
+
+    let tasks = [first, second].map(|sub| {
+        spawn(async move {
+            sub.wait(|x| x > 0).await;
+            sub.changed().skip(1).next().await
+        })
+    });
+    sleep(SMALL_TIMEOUT).await;
+    ATOMIC.store(1);
+    sleep(SMALL_TIMEOUT).await;
+    ATOMIC.store(2);
+    for task in tasks {
+        assert_eq!(timeout(BIG_TIMEOUT, task).await.unwrap(), Some(2));
+    }
+    assert!(ATOMIC.try_subscribe().is_some());
+}
+
+#[async_test]
+async fn broadcast() {
+    static BROADCAST: Broadcast<u32, 4, 2> = Broadcast::<u32, 4, 2>::const_new();
+    let sender = BROADCAST.try_sender().unwrap();
+    assert!(BROADCAST.try_sender().is_none());
+    let mut slow = BROADCAST.subscribe();
+
+    let fast = BROADCAST.subscribe();
+    let task = spawn(fast.take(8).collect::<Vec<_>>());
+    for i in 0..8 {
+        sleep(SMALL_TIMEOUT).await;
+        sender.send(i);
+    }
+    assert_eq!(
+        timeout(BIG_TIMEOUT, task).await.unwrap(),
+        (0..8).map(Ok).collect::<Vec<_>>()
+    );
+
+    assert_eq!(BROADCAST.latest(), Some(7));
+    assert_eq!(
+        slow.drain().collect::<Vec<_>>(),
+        [Err(Lagged(4)), Ok(4), Ok(5), Ok(6), Ok(7)]
+    );
+    assert_eq!(slow.try_recv(), Ok(None));
+    sender.send(8);
+    assert_eq!(slow.drain().collect::<Vec<_>>(), [Ok(8)]);
+}
+
+#[test]
+fn broadcast_overwritten_while_receiving() {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    static BROADCAST: std::sync::LazyLock<Broadcast<Probe, 4, 1>> =
+        std::sync::LazyLock::new(Broadcast::new);
+    static WRAP: AtomicBool = AtomicBool::new(false);
+
+    /// Wraps the ring when loaded from a slot, i.e. after the receiver has loaded the head.
+    #[derive(Clone, Copy, Default, PartialEq, Debug)]
+    struct Probe(u32);
+    impl Atom for Probe {
+        type Repr = u32;
+        fn pack(self) -> u32 {
+            self.0
+        }
+        fn unpack(src: u32) -> Self {
+            if WRAP.swap(false, Ordering::Relaxed) {
+                let sender = BROADCAST.try_sender().unwrap();
+                (1..5).for_each(|i| sender.send(Probe(i)));
+            }
+            Probe(src)
+        }
+    }
+
+    let mut receiver = BROADCAST.subscribe();
+    BROADCAST.try_sender().unwrap().send(Probe(0));
+    WRAP.store(true, Ordering::Relaxed);
+    assert_eq!(receiver.try_recv(), Err(Lagged(1)));
+    assert_eq!(
+        receiver.drain().collect::<Vec<_>>(),
+        (1..5).map(|i| Ok(Probe(i))).collect::<Vec<_>>()
+    );
+}
+
+#[async_test]
+async fn multi_fan_out() {
+    static ATOMIC: AsyncAtomicMulti<usize, 3> = AsyncAtomicMulti::<usize, 3>::const_new(0);
+
+    let tasks = (0..3)
+        .map(|_| {
+            let mut sub = ATOMIC.subscribe();
+            spawn(async move {
+                let mut values = Vec::new();
+                while values.last() != Some(&3) {
+                    values.push(sub.updated().await);
+                }
+                assert!(!sub.has_changed());
+                values
+            })
+        })
+        .collect::<Vec<_>>();
+    for i in 1..=3 {
+        sleep(SMALL_TIMEOUT).await;
+        ATOMIC.store(i);
+    }
+    for task in tasks {
+        assert_eq!(timeout(BIG_TIMEOUT, task).await.unwrap(), [1, 2, 3]);
+    }
+}
+
+#[async_test]
+async fn deltas() {
+    let atomic = AsyncAtomic::<u8>::new(250);
+    let mut stream = (&atomic).deltas();
+
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+    atomic.fetch_add(1);
+    atomic.fetch_add(2);
+    assert_eq!(stream.next().await, Some(3));
+    atomic.fetch_add(10);
+    assert_eq!(stream.next().await, Some(10));
+    assert_eq!(atomic.load(), 7);
+}
+
+#[async_test]
+async fn updates_with_lag() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut stream = (&atomic).updates().with_lag();
+
+    atomic.store(1);
+    assert_eq!(stream.next().await, Some(Ok(1)));
+    atomic.store(2);
+    atomic.store(3);
+    atomic.store(4);
+    assert_eq!(stream.next().await, Some(Err(Lagged(2))));
+    assert_eq!(stream.next().await, Some(Ok(4)));
+    assert!(timeout(SMALL_TIMEOUT, stream.next()).await.is_err());
+}
+
+#[test]
+fn try_wait() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+    assert_eq!(atomic.try_wait(|x| x > 0), None);
+    atomic.store(2);
+    assert_eq!(atomic.try_wait(|x| x > 0), Some(2));
+
+    let mut changed = (&atomic).changed();
+    assert_eq!(changed.try_changed(), Some(2));
+    assert_eq!(changed.try_changed(), None);
+    atomic.store(2);
+    assert_eq!(changed.try_changed(), None);
+    atomic.store(3);
+    assert_eq!(changed.try_changed(), Some(3));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn blocking_subscriber() {
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0));
+    let other = atomic.clone();
+    let thread = std::thread::spawn(move || other.subscribe_blocking().take(3).collect::<Vec<_>>());
+    for i in 1..=2 {
+        std::thread::sleep(SMALL_TIMEOUT);
+        atomic.store(i);
+    }
+    assert_eq!(thread.join().unwrap(), [0, 1, 2]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn blocking_subscriber_thread_parker() {
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0));
+    let other = atomic.clone();
+    let thread = std::thread::spawn(move || {
+        crate::BlockingSubscriber::<_, crate::ThreadParker>::new(other)
+            .take(3)
+            .collect::<Vec<_>>()
+    });
+    for i in 1..=2 {
+        std::thread::sleep(SMALL_TIMEOUT);
+        atomic.store(i);
+    }
+    assert_eq!(thread.join().unwrap(), [0, 1, 2]);
+}
+
+#[async_test]
+async fn fused_futures() {
+    use futures::future::FusedFuture;
+
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut wait = atomic.wait(|x| x > 0);
+    let mut update = atomic.wait_and_update(|x| (x == 1).then_some(2));
+    assert!(!wait.is_terminated() && !update.is_terminated());
+
+    atomic.store(1);
+    (&mut wait).await;
+    assert!(wait.is_terminated());
+    assert_eq!((&mut update).await, 1);
+    assert!(update.is_terminated());
+    assert!(!FusedFuture::is_terminated(&(&atomic).changed()));
+}
+
+#[async_test]
+async fn budget() {
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut stream = (&atomic).updates().with_budget(2);
+    let mut polls = 0;
+    let mut values = Vec::new();
+    poll_fn(|cx| {
+        polls += 1;
+        while values.len() < 4 {
+            atomic.fetch_add(1);
+            match stream.poll_next_unpin(cx) {
+                Poll::Ready(x) => values.push(x.unwrap()),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(())
+    })
+    .await;
+    assert_eq!(values, [1, 2, 4, 5]);
+    assert_eq!(polls, 2);
+}
+
+#[async_test]
+async fn wait_with_spin() {
+    let atomic = Arc::new(AsyncAtomic::<usize>::new(0));
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait(|x| x > 0).with_spin(16))
+        .await
+        .is_err());
+
+    let other = atomic.clone();
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        other.store(1);
+    });
+    timeout(BIG_TIMEOUT, atomic.wait(|x| x > 0).with_spin(16))
+        .await
+        .unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wake_coalescing() {
+    use core::{future::Future, pin::pin, sync::atomic::Ordering, task::Context};
+    use std::task::{Wake, Waker};
+
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let counter = Arc::new(Counter::default());
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let atomic = AsyncAtomic::<usize>::new(0);
+    let mut wait = pin!(atomic.wait(|x| x > 10));
+    assert!(wait.as_mut().poll(&mut cx).is_pending());
+    for i in 1..=3 {
+        atomic.store(i);
+    }
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+    assert!(wait.as_mut().poll(&mut cx).is_pending());
+    atomic.store(4);
+    atomic.store(5);
+    assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+}
+
+#[async_test]
+async fn time() {
+    use crate::{AsyncAtomicDuration, AsyncAtomicTick, Tick};
+
+    let ticks = AsyncAtomicTick::new(Tick(0));
+    assert!(timeout(SMALL_TIMEOUT, ticks.wait_reached(Tick(2)))
+        .await
+        .is_err());
+    ticks.fetch_add(Tick(3));
+    timeout(SMALL_TIMEOUT, ticks.wait_reached(Tick(2)))
+        .await
+        .unwrap();
+
+    let elapsed = AsyncAtomicDuration::zero();
+    assert_eq!(elapsed.fetch_add(Duration::from_millis(5)), Duration::ZERO);
+    assert!(timeout(
+        SMALL_TIMEOUT,
+        elapsed.wait_at_least(Duration::from_millis(10))
+    )
+    .await
+    .is_err());
+    elapsed.fetch_add(Duration::from_millis(5));
+    timeout(
+        SMALL_TIMEOUT,
+        elapsed.wait_at_least(Duration::from_millis(10)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(elapsed.fetch_sub(Duration::MAX), Duration::from_millis(10));
+    assert_eq!(elapsed.load(), Duration::ZERO);
+    elapsed.store(Duration::MAX);
+    assert_eq!(elapsed.load(), Duration::from_nanos(u64::MAX));
+}
+
+#[async_test]
+async fn stamped() {
+    let atomic = AsyncAtomic::new(Stamped::new(1u32, u32::MAX));
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait_newer_than(u32::MAX))
+        .await
+        .is_err());
+    atomic.store(Stamped::new(2, 0));
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, atomic.wait_newer_than(u32::MAX))
+            .await
+            .unwrap(),
+        Stamped::new(2, 0)
+    );
+    assert!(!atomic.load().is_newer_than(1));
+}
+
+#[async_test]
+async fn stats() {
+    use crate::{AsyncStats, StatsSnapshot};
+
+    let stats = Arc::new(AsyncStats::new());
+    assert_eq!(stats.snapshot(), StatsSnapshot::default());
+
+    let producer = stats.clone();
+    spawn(async move {
+        for x in [3, 1, 5] {
+            sleep(SMALL_TIMEOUT).await;
+            producer.record(x);
+        }
+    });
+    timeout(BIG_TIMEOUT, stats.wait_count_at_least(3))
+        .await
+        .unwrap();
+    let snapshot = stats.take();
+    assert_eq!(
+        snapshot,
+        StatsSnapshot {
+            count: 3,
+            sum: 9,
+            min: Some(1),
+            max: Some(5),
+        }
+    );
+    assert_eq!(snapshot.mean(), Some(3.0));
+    assert_eq!(stats.snapshot(), StatsSnapshot::default());
+
+    // Sum saturates instead of wrapping around.
+    stats.record(u32::MAX - 1);
+    stats.record(2);
+    let snapshot = stats.take();
+    assert_eq!(snapshot.sum, u32::MAX);
+    assert!(snapshot.is_saturated());
+    assert_eq!(snapshot.mean(), None);
+}
+
+#[async_test]
+async fn watermark() {
+    use crate::Watermark;
+    use futures::FutureExt;
+
+    let depth = Arc::new(Watermark::new(2));
+    let producer = depth.clone();
+    spawn(async move {
+        for _ in 0..3 {
+            sleep(SMALL_TIMEOUT).await;
+            producer.add(2);
+        }
+        producer.sub(7);
+    });
+    timeout(BIG_TIMEOUT, depth.wait_above(7)).await.unwrap();
+    timeout(BIG_TIMEOUT, depth.wait_below(2)).await.unwrap();
+    assert_eq!((depth.load(), depth.low(), depth.high()), (1, 1, 8));
+    depth.reset();
+    assert_eq!((depth.low(), depth.high()), (1, 1));
+
+    // Transient peak stored before the waiter is polled.
+    depth.store(9);
+    depth.store(1);
+    assert_eq!(depth.wait_above(8).now_or_never(), Some(()));
+    assert_eq!(depth.wait_below(1).now_or_never(), None);
+}
+
+#[async_test]
+async fn progress() {
+    use crate::Progress;
+
+    let progress = Arc::new(Progress::new(0));
+    let mut percents = progress.changed();
+    assert_eq!(percents.next().await, Some(0));
+
+    let worker = progress.clone();
+    spawn(async move {
+        worker.set_total(400);
+        for _ in 0..400 {
+            worker.inc();
+        }
+    });
+    timeout(BIG_TIMEOUT, progress.wait_finished())
+        .await
+        .unwrap();
+    assert_eq!(progress.percent(), 100);
+    assert_eq!(percents.next().await, Some(100));
+}
+
+#[async_test]
+async fn watchdog() {
+    use crate::Watchdog;
+
+    let watchdog = Arc::new(Watchdog::new());
+    let task = watchdog.clone();
+    spawn(async move {
+        for _ in 0..5 {
+            sleep(SMALL_TIMEOUT).await;
+            task.pet();
+        }
+    });
+    timeout(BIG_TIMEOUT, watchdog.wait_stale(&sleep, SMALL_TIMEOUT * 5))
+        .await
+        .unwrap();
+    assert_eq!(watchdog.pets(), 5);
+}
+
+#[async_test]
+async fn rate_meter() {
+    use crate::RateMeter;
+
+    static METER: RateMeter = RateMeter::new();
+    spawn(METER.measure(&sleep, SMALL_TIMEOUT * 5));
+    spawn(async {
+        for _ in 0..10 {
+            METER.record_n(10);
+            sleep(SMALL_TIMEOUT).await;
+        }
+    });
+    timeout(BIG_TIMEOUT, METER.wait_rate_above(0))
+        .await
+        .unwrap();
+    timeout(BIG_TIMEOUT, METER.wait_rate_below(1))
+        .await
+        .unwrap();
+    assert_eq!(METER.events(), 100);
+}
+
+#[async_test]
+async fn array() {
+    use crate::AsyncAtomicArray;
+
+    let table = Arc::new(AsyncAtomicArray::<usize, 3>::default());
+    let tasks = (0..3)
+        .map(|i| {
+            let table = table.clone();
+            spawn(async move { table.wait(i, |x| x > 0).await })
+        })
+        .collect::<Vec<_>>();
+    sleep(SMALL_TIMEOUT).await;
+    for i in 0..3 {
+        table.store(i, i + 1);
+    }
+    for task in tasks {
+        timeout(BIG_TIMEOUT, task).await.unwrap();
+    }
+    assert_eq!(Arc::try_unwrap(table).unwrap().into_inner(), [1, 2, 3]);
+}
+
+#[async_test]
+async fn array_any_changed() {
+    use crate::AsyncAtomicArray;
+
+    let table = AsyncAtomicArray::new([0usize, 10, 20]);
+    assert_eq!(table.changed(1).next().await, Some(10));
+
+    let mut any = table.any_changed();
+    let mut initial = Vec::new();
+    for _ in 0..3 {
+        initial.push(any.next().await.unwrap());
+    }
+    assert_eq!(initial, [(0, 0), (1, 10), (2, 20)]);
+    assert!(timeout(SMALL_TIMEOUT, any.next()).await.is_err());
+
+    table.store(2, 21);
+    table.store(0, 1);
+    assert_eq!(any.next().await, Some((0, 1)));
+    assert_eq!(any.next().await, Some((2, 21)));
+    table.store(1, 10);
+    assert!(timeout(SMALL_TIMEOUT, any.next()).await.is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[async_test]
+async fn registry() {
+    use crate::AtomicRegistry;
+
+    let mut registry = AtomicRegistry::<&str, usize>::new();
+    let eth0 = registry.insert("eth0", 0);
+    registry.insert("eth1", 0);
+    registry.insert("lo", 1);
+
+    let wait = registry.wait_any(["eth0", "eth1", "wlan0"], |_, x| x > 0);
+    assert_eq!(registry.remove(&"eth1").map(|a| a.load()), Some(0));
+    assert_eq!(registry.len(), 2);
+
+    spawn(async move {
+        sleep(SMALL_TIMEOUT).await;
+        eth0.store(3);
+    });
+    assert_eq!(timeout(BIG_TIMEOUT, wait).await, Ok(("eth0", 3)));
+}
+
+#[cfg(feature = "std")]
+#[async_test]
+async fn bus() {
+    use crate::bus;
+
+    assert!(!bus::contains("test.level"));
+    let level = bus::get::<usize>("test.level");
+    spawn(async {
+        sleep(SMALL_TIMEOUT).await;
+        bus::get::<usize>("test.level").store(5);
+    });
+    timeout(BIG_TIMEOUT, level.wait(|x| x == 5)).await.unwrap();
+
+    assert!(std::panic::catch_unwind(|| bus::get::<u8>("test.level")).is_err());
+    assert!(bus::remove("test.level"));
+    assert_eq!(bus::get::<u8>("test.level").load(), 0);
+}
+
+#[async_test]
+async fn signal_group() {
+    use crate::{GroupMember, SignalGroup};
+
+    static ROOT: SignalGroup = SignalGroup::new();
+    static POWER: SignalGroup = SignalGroup::with_parent(&ROOT);
+
+    let voltage = GroupMember::new(0u32, &POWER);
+    let mut power = (&POWER).updates();
+    let mut root = (&ROOT).updates();
+
+    voltage.store(3700);
+    assert_eq!(timeout(SMALL_TIMEOUT, power.next()).await.unwrap(), Some(1));
+    assert_eq!(timeout(SMALL_TIMEOUT, root.next()).await.unwrap(), Some(1));
+    assert_eq!((&voltage).changed().next().await, Some(3700));
+    assert!(voltage.compare_exchange(0, 1).is_err());
+    assert_eq!(ROOT.as_atomic().load(), 1);
+}
+
+#[async_test]
+async fn reactive_drive() {
+    use crate::Computed;
+
+    static INPUT: AsyncAtomicU64 = AsyncAtomicU64::const_new(1);
+    static SQUARE: std::sync::LazyLock<Computed<u64, fn() -> u64, 1>> =
+        std::sync::LazyLock::new(|| Computed::new([&INPUT], || INPUT.load() * INPUT.load()));
+
+    spawn(SQUARE.drive());
+    let mut squares = (&*SQUARE).changed();
+    assert_eq!(squares.next().await, Some(1));
+    sleep(SMALL_TIMEOUT).await;
+    INPUT.store(3);
+    assert_eq!(timeout(BIG_TIMEOUT, squares.next()).await.unwrap(), Some(9));
+    INPUT.store(3);
+    assert!(timeout(SMALL_TIMEOUT, squares.next()).await.is_err());
+}
+
+#[async_test]
+async fn memo_wakes_on_derived_change() {
+    use crate::Memo;
+
+    let source = Arc::new(AsyncAtomic::<u32>::new(1));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let memo = Memo::new(source.clone(), {
+        let calls = calls.clone();
+        move |x| {
+            calls.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            x / 10
+        }
+    });
+    assert_eq!(calls.load(core::sync::atomic::Ordering::Relaxed), 1);
+    assert_eq!(memo.get(), 0);
+    assert_eq!(memo.get(), 0);
+    assert_eq!(calls.load(core::sync::atomic::Ordering::Relaxed), 1);
+
+    let mut changed = memo.changed();
+    assert_eq!(changed.next().await, Some(0));
+    let producer = spawn({
+        let source = source.clone();
+        async move {
+            sleep(SMALL_TIMEOUT).await;
+            source.store(5);
+            sleep(SMALL_TIMEOUT).await;
+            source.store(12);
+        }
+    });
+    assert_eq!(timeout(BIG_TIMEOUT, changed.next()).await.unwrap(), Some(1));
+    producer.await;
+    // At most one recompute per generation.
+    assert!(calls.load(core::sync::atomic::Ordering::Relaxed) <= 3);
+}
+
+#[async_test]
+async fn forward_changes_until_cancelled() {
+    use crate::forward_changes;
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0));
+    let cancel = Arc::new(CancelToken::new());
+    let seen = Arc::new(AtomicUsize::new(0));
+    let task = spawn({
+        let (atomic, cancel, seen) = (atomic.clone(), cancel.clone(), seen.clone());
+        async move {
+            forward_changes(
+                &*atomic,
+                |value| {
+                    let seen = seen.clone();
+                    async move {
+                        sleep(SMALL_TIMEOUT).await;
+                        seen.store(value as usize, core::sync::atomic::Ordering::Release);
+                    }
+                },
+                &*cancel,
+            )
+            .await
+        }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    atomic.store(1);
+    atomic.store(2);
+    sleep(BIG_TIMEOUT).await;
+    assert_eq!(seen.load(core::sync::atomic::Ordering::Acquire), 2);
+    cancel.cancel();
+    timeout(BIG_TIMEOUT, task).await.unwrap();
+}
+
+#[cfg(feature = "std")]
+#[async_test]
+async fn counting_io() {
+    use crate::{CountingReader, CountingWriter};
+    use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+    use std::vec;
+
+    let read = AsyncAtomicU64::new(0);
+    let written = AsyncAtomicU64::new(0);
+    let mut reader = CountingReader::new(Cursor::new(vec![7u8; 100]), &read);
+    let mut writer = CountingWriter::new(Cursor::new(Vec::new()), &written);
+
+    let mut buf = [0u8; 30];
+    loop {
+        let n = reader.read(&mut buf).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n]).await.unwrap();
+    }
+    writer.flush().await.unwrap();
+    timeout(SMALL_TIMEOUT, read.wait(|n| n == 100))
+        .await
+        .unwrap();
+    assert_eq!(written.load(), 100);
+    assert_eq!(writer.into_inner().into_inner(), vec![7u8; 100]);
+}
+
+#[async_test]
+async fn has_waiter() {
+    use core::{future::Future, pin::Pin, task::Context};
+    use futures::task::noop_waker;
+
+    let atomic = AsyncAtomic::<u32>::new(0);
+    assert!(!atomic.has_waiter());
+    let mut changed = (&atomic).changed();
+    assert_eq!(changed.next().await, Some(0));
+    assert!(timeout(SMALL_TIMEOUT, changed.next()).await.is_err());
+    assert!(atomic.has_waiter());
+    atomic.store(1);
+    assert!(!atomic.has_waiter());
+    // With the default policy a completed wait stays registered until the next store.
+    atomic.wait(|x| x == 1).await;
+    assert!(atomic.has_waiter());
+    atomic.store(1);
+    assert!(!atomic.has_waiter());
+
+    let multi = AsyncAtomicMulti::<u32, 2>::new(0);
+    assert!(!multi.has_waiter());
+    let mut first = multi.subscribe();
+    let second = multi.subscribe();
+    // Idle subscribers are not waiting.
+    assert!(!multi.has_waiter());
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut first_wait = first.updated();
+    let mut second_wait = second.wait(|x| x == 1);
+    assert!(Pin::new(&mut first_wait).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut second_wait).poll(&mut cx).is_pending());
+    assert_eq!(multi.waiter_count(), 2);
+    // Cancelled wait doesn't occupy the slot anymore.
+    drop(first_wait);
+    assert_eq!(multi.waiter_count(), 1);
+    multi.store(1);
+    assert!(!multi.has_waiter());
+    assert!(Pin::new(&mut second_wait).poll(&mut cx).is_ready());
+    assert!(!multi.has_waiter());
+}
+
+#[async_test]
+async fn multi_notify_one() {
+    use crate::NotifyPolicy;
+
+    let atomic = Arc::new(AsyncAtomicMulti::<u32, 2>::new(0).with_policy(NotifyPolicy::One));
+    let woken = Arc::new(AtomicUsize::new(0));
+    let tasks: Vec<_> = (0..2)
+        .map(|_| {
+            let (atomic, woken) = (atomic.clone(), woken.clone());
+            spawn(async move {
+                let mut subscriber = atomic.subscribe();
+                subscriber.updated().await;
+                woken.fetch_add(1, core::sync::atomic::Ordering::AcqRel);
+            })
+        })
+        .collect();
+    sleep(SMALL_TIMEOUT).await;
+    atomic.store(1);
+    sleep(SMALL_TIMEOUT).await;
+    assert_eq!(woken.load(core::sync::atomic::Ordering::Acquire), 1);
+    atomic.store_with(2, NotifyPolicy::All);
+    for task in tasks {
+        timeout(BIG_TIMEOUT, task).await.unwrap();
+    }
+    assert_eq!(woken.load(core::sync::atomic::Ordering::Acquire), 2);
+}
+
+#[cfg(feature = "std")]
+#[async_test]
+async fn wait_and_update_panic() {
+    use core::{future::Future, pin::pin};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let atomic = AsyncAtomic::<u32>::new(1);
+    let mut update = pin!(atomic.wait_and_update(|_| -> Option<u32> { panic!("map failed") }));
+    poll_fn(|cx| {
+        assert!(catch_unwind(AssertUnwindSafe(|| update.as_mut().poll(cx))).is_err());
+        Poll::Ready(())
+    })
+    .await;
+    assert_eq!(atomic.load(), 1);
+    assert!(!atomic.has_waiter());
+
+    let consumer = atomic.wait_and_update(|x| (x == 2).then_some(3));
+    atomic.store(2);
+    assert_eq!(timeout(SMALL_TIMEOUT, consumer).await.unwrap(), 2);
+    assert_eq!(atomic.load(), 3);
+}
+
+#[cfg(feature = "std")]
+#[async_test]
+async fn wait_and_update_contention() {
+    use core::{future::Future, pin::pin};
+    use std::thread;
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0));
+    let writers: Vec<_> = (0..4)
+        .map(|_| {
+            let atomic = atomic.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    atomic.fetch_add(1);
+                }
+            })
+        })
+        .collect();
+    let mut updates = 0;
+    for _ in 0..1000 {
+        // `map` always returns `Some`, so contention must never suspend the future.
+        poll_fn(|cx| {
+            let update = pin!(atomic.wait_and_update(|x| Some(x + 1)));
+            assert!(update.poll(cx).is_ready());
+            Poll::Ready(())
+        })
+        .await;
+        updates += 1;
+    }
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    assert_eq!(atomic.load(), 4000 + updates);
+}
+
+#[async_test]
+async fn wait_changed_from() {
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0));
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, atomic.wait_changed_from(1))
+            .await
+            .unwrap(),
+        0
+    );
+    let producer = spawn({
+        let atomic = atomic.clone();
+        async move {
+            sleep(SMALL_TIMEOUT).await;
+            atomic.store(0);
+            sleep(SMALL_TIMEOUT).await;
+            atomic.store(2);
+        }
+    });
+    let mut last = 0;
+    last = timeout(BIG_TIMEOUT, atomic.wait_changed_from(last))
+        .await
+        .unwrap();
+    assert_eq!(last, 2);
+    producer.await;
+}
+
+#[async_test]
+async fn wait_stable() {
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(1));
+    let producer = spawn({
+        let atomic = atomic.clone();
+        async move {
+            for value in [1, 0, 1, 1, 1] {
+                sleep(SMALL_TIMEOUT).await;
+                atomic.store(value);
+            }
+        }
+    });
+    let mut stable = atomic.wait_stable(|x| x == 1, 3);
+    timeout(BIG_TIMEOUT, &mut stable).await.unwrap();
+    // Glitch reset the streak, so the future resolves only on the last store.
+    assert_eq!(atomic.generation(), 5);
+    assert_eq!(stable.streak, 3);
+    producer.await;
+}
+
+#[cfg(feature = "alloc")]
+#[async_test]
+async fn split_changed_terminates() {
+    use crate::split;
+    use futures::stream::FusedStream;
+
+    let (producer, consumer) = split::<u32>(0);
+    let mut changed = consumer.changed();
+    assert_eq!(changed.next().await, Some(0));
+    let task = spawn({
+        let producer = producer.clone();
+        async move {
+            sleep(SMALL_TIMEOUT).await;
+            producer.store(1);
+        }
+    });
+    drop(producer);
+    assert_eq!(timeout(BIG_TIMEOUT, changed.next()).await.unwrap(), Some(1));
+    task.await;
+    assert_eq!(timeout(BIG_TIMEOUT, changed.next()).await.unwrap(), None);
+    assert!(changed.is_terminated());
+    assert_eq!(changed.next().await, None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn split_close() {
+    use crate::{split, Closed};
+
+    let (producer, consumer) = split::<u32>(0);
+    assert_eq!(producer.try_store(1), Ok(()));
+    assert_eq!(consumer.load(), 1);
+    consumer.close();
+    assert!(producer.is_closed());
+    assert_eq!(producer.try_store(2), Err(Closed(2)));
+    assert_eq!(consumer.load(), 1);
+
+    let (producer, consumer) = split::<u32>(0);
+    drop(consumer);
+    assert_eq!(producer.try_store(1), Err(Closed(1)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn split_counts() {
+    use crate::split;
+
+    let (producer, consumer) = split::<u32>(0);
+    assert_eq!(
+        (producer.producer_count(), producer.subscriber_count()),
+        (1, 1)
+    );
+    let other = producer.clone();
+    assert_eq!(consumer.producer_count(), 2);
+    drop(producer);
+    assert_eq!(consumer.producer_count(), 1);
+    drop(consumer);
+    assert_eq!((other.producer_count(), other.subscriber_count()), (1, 0));
+}
+
+#[cfg(feature = "alloc")]
+#[async_test]
+async fn weak_subscriber() {
+    use crate::{Dropped, WeakSubscriber};
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0));
+    let mut subscriber = WeakSubscriber::new(&atomic);
+    assert_eq!(subscriber.has_changed(), Ok(false));
+    let producer = spawn({
+        let atomic = atomic.clone();
+        async move {
+            sleep(SMALL_TIMEOUT).await;
+            atomic.store(1);
+        }
+    });
+    assert_eq!(
+        timeout(BIG_TIMEOUT, subscriber.updated()).await.unwrap(),
+        Ok(1)
+    );
+    producer.await;
+
+    let task = spawn({
+        let subscriber = subscriber.clone();
+        async move { subscriber.wait(|x| x == 2).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    drop(atomic);
+    assert_eq!(timeout(BIG_TIMEOUT, task).await.unwrap(), Err(Dropped));
+    assert_eq!(subscriber.has_changed(), Err(Dropped));
+}
+
+#[cfg(feature = "derive")]
+#[async_test]
+async fn derived_atoms() {
+    use crate::{Atom, AtomInteger};
+
+    #[derive(Atom, Clone, Copy, PartialEq, Eq, Debug)]
+    #[repr(u8)]
+    enum Mode {
+        Off,
+        Standby,
+        On = 7,
+    }
+
+    #[derive(Atom, Clone, Copy, PartialEq, Eq, Debug)]
+    struct Count(u32);
+    impl AtomInteger for Count {}
+
+    for mode in [Mode::Off, Mode::Standby, Mode::On] {
+        assert_eq!(Mode::unpack(mode.pack()), mode);
+    }
+
+    let mode = Arc::new(AsyncAtomic::new(Mode::Off));
+    let mut changes = (&*mode).changed();
+    assert_eq!(changes.next().await, Some(Mode::Off));
+    let task = spawn({
+        let mode = mode.clone();
+        async move {
+            mode.wait_state(Mode::Standby).await;
+            mode.compare_exchange(Mode::Standby, Mode::On).unwrap();
+        }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    mode.store(Mode::Standby);
+    timeout(BIG_TIMEOUT, task).await.unwrap();
+    assert_eq!(mode.fetch_update(|_| Some(Mode::Off)), Ok(Mode::On));
+
+    let count = AsyncAtomic::new(Count(1));
+    assert_eq!(count.fetch_add(Count(2)), Count(1));
+    assert_eq!(count.load(), Count(3));
+
+    // Invalid bit pattern is rejected by `unpack` and the atomic keeps its valid value.
+    assert!(std::panic::catch_unwind(|| Mode::unpack(3)).is_err());
+    let erased: &dyn crate::DynAsyncAtomic = &*mode;
+    assert!(
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| erased.store_raw(3))).is_err()
+    );
+    assert_eq!(mode.load(), Mode::Off);
+    erased.store_raw(7);
+    assert_eq!(mode.load(), Mode::On);
+}
+
+#[async_test]
+async fn dyn_async_atomic() {
+    use crate::DynAsyncAtomic;
+
+    static FLAG: AsyncAtomic<bool> = AsyncAtomic::<bool>::const_new(false);
+    static LEVEL: AsyncAtomic<i8> = AsyncAtomic::<i8>::const_new(-1);
+    let signals: [&'static dyn DynAsyncAtomic; 2] = [&FLAG, &LEVEL];
+    assert_eq!(signals[0].type_name(), "bool");
+    assert_eq!(signals[1].load_raw(), u64::MAX);
+
+    let task = spawn(signals[1].wait_raw(|bits| bits == 3));
+    sleep(SMALL_TIMEOUT).await;
+    signals[1].store_raw(3);
+    timeout(BIG_TIMEOUT, task).await.unwrap();
+    assert_eq!(LEVEL.load(), 3);
+    signals[0].store_raw(1);
+    assert!(FLAG.load());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn snapshot_consistent() {
+    use crate::snapshot;
+    use std::thread;
+
+    // Writer stores `a` and then `b`, so `b` can never be ahead of `a` in a consistent snapshot.
+    let atomics = Arc::new((AsyncAtomic::<u32>::new(0), AsyncAtomic::<u32>::new(0)));
+    let writer = thread::spawn({
+        let atomics = atomics.clone();
+        move || {
+            let (a, b) = &*atomics;
+            for i in 1..=10000 {
+                a.store(i);
+                b.store(i);
+            }
+        }
+    });
+    let (a, b) = &*atomics;
+    for _ in 0..1000 {
+        let (x, y) = snapshot!((a, b));
+        assert!(x == y || x == y + 1);
+    }
+    writer.join().unwrap();
+    assert_eq!(snapshot!((a, b)), (10000, 10000));
+}
+
+#[cfg(feature = "std")]
+#[async_test]
+async fn transaction_transfers() {
+    use crate::transaction;
+    use std::thread;
+
+    static A: AsyncAtomic<u32> = AsyncAtomic::<u32>::const_new(1000);
+    static B: AsyncAtomic<u32> = AsyncAtomic::<u32>::const_new(0);
+
+    let watcher = spawn(B.wait(|x| x == 1000));
+    sleep(SMALL_TIMEOUT).await;
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                for _ in 0..250 {
+                    transaction(|tx| {
+                        let (a, b) = (tx.read(&A), tx.read(&B));
+                        tx.write(&A, a - 1);
+                        tx.write(&B, b + 1);
+                    });
+                    let (a, b) = transaction(|tx| (tx.read(&A), tx.read(&B)));
+                    assert_eq!(a + b, 1000);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert_eq!((A.load(), B.load()), (0, 1000));
+    timeout(BIG_TIMEOUT, watcher).await.unwrap();
+}
+
+#[async_test]
+async fn atomic_pair() {
+    use crate::AtomicPair;
+
+    let pair = Arc::new(AtomicPair::<i16, u32>::new(-1, 10));
+    assert_eq!(pair.load(), (-1, 10));
+    assert_eq!(pair.compare_exchange((-1, 11), (0, 0)), Err((-1, 10)));
+    assert_eq!(pair.compare_exchange((-1, 10), (-2, 20)), Ok((-1, 10)));
+    assert_eq!(pair.store_second(u32::MAX), (-2, 20));
+    assert_eq!(pair.load(), (-2, u32::MAX));
+
+    let task = spawn({
+        let pair = pair.clone();
+        async move { pair.wait_first(|a| a == 5).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    pair.store_second(0);
+    sleep(SMALL_TIMEOUT).await;
+    pair.store_first(5);
+    timeout(BIG_TIMEOUT, task).await.unwrap();
+    assert_eq!(pair.load(), (5, 0));
+}
+
+#[async_test]
+async fn multi_priority() {
+    use crate::NotifyPolicy;
+
+    let atomic = Arc::new(AsyncAtomicMulti::<u32, 3>::new(0).with_policy(NotifyPolicy::One));
+    let order = Arc::new(AsyncAtomic::<u32>::new(0));
+    let tasks: Vec<_> = [1, 9, 5]
+        .into_iter()
+        .map(|priority| {
+            let (atomic, order) = (atomic.clone(), order.clone());
+            spawn(async move {
+                let mut subscriber = atomic.subscribe_with_priority(priority);
+                subscriber.updated().await;
+                // Record priorities in the order of wake-ups as decimal digits.
+                order
+                    .fetch_update(|x| Some(x * 10 + priority as u32))
+                    .unwrap();
+            })
+        })
+        .collect();
+    sleep(SMALL_TIMEOUT).await;
+    for value in 1..=3 {
+        atomic.store(value);
+        sleep(SMALL_TIMEOUT).await;
+    }
+    for task in tasks {
+        timeout(BIG_TIMEOUT, task).await.unwrap();
+    }
+    assert_eq!(order.load(), 951);
+}
+
+#[async_test]
+async fn multi_fifo() {
+    use crate::NotifyPolicy;
+
+    static ATOMIC: AsyncAtomicMulti<u32, 3> =
+        AsyncAtomicMulti::<u32, 3>::const_new(0).with_policy(NotifyPolicy::Fifo);
+    let order = Arc::new(AsyncAtomic::<u32>::new(0));
+    let mut subscribers: Vec<_> = (1..=3).map(|_| Some(ATOMIC.subscribe())).collect();
+    // Subscribers start waiting in the order different from the order of their slots.
+    let mut tasks = Vec::new();
+    for id in [3, 1, 2] {
+        let mut subscriber = subscribers[id - 1].take().unwrap();
+        let order = order.clone();
+        tasks.push(spawn(async move {
+            subscriber.updated().await;
+            // Record ids in the order of wake-ups as decimal digits.
+            order.fetch_update(|x| Some(x * 10 + id as u32)).unwrap();
+        }));
+        sleep(SMALL_TIMEOUT).await;
+    }
+    for value in 1..=3 {
+        ATOMIC.store(value);
+        sleep(SMALL_TIMEOUT).await;
+    }
+    for task in tasks {
+        timeout(BIG_TIMEOUT, task).await.unwrap();
+    }
+    assert_eq!(order.load(), 312);
+}
+
+#[async_test]
+async fn waker_policy_reject() {
+    use crate::{Occupied, WakerPolicy};
+    use futures::task::noop_waker;
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0).with_waker_policy(WakerPolicy::Reject));
+    let task = spawn({
+        let atomic = atomic.clone();
+        async move { atomic.wait(|x| x == 1).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    // Another waker is rejected while the task is waiting.
+    assert_eq!(atomic.try_register(&noop_waker()), Err(Occupied));
+    atomic.store(1);
+    timeout(BIG_TIMEOUT, task).await.unwrap();
+    // The task was woken, so the atomic is free again.
+    assert_eq!(atomic.try_register(&noop_waker()), Ok(()));
+}
+
+#[async_test]
+async fn waker_policy_reject_cancelled() {
+    use crate::{Occupied, WakerPolicy};
+    use futures::task::noop_waker;
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0).with_waker_policy(WakerPolicy::Reject));
+    // Cancelled wait and stream release the atomic when dropped.
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait(|x| x == 1))
+        .await
+        .is_err());
+    assert!(!atomic.has_waiter());
+    let mut changes = atomic.clone().changed();
+    assert_eq!(changes.next().await, Some(0));
+    assert!(timeout(SMALL_TIMEOUT, changes.next()).await.is_err());
+    drop(changes);
+    assert!(!atomic.has_waiter());
+    assert_eq!(atomic.try_register(&noop_waker()), Ok(()));
+    atomic.store(0);
+
+    // Dropping a wait while another task occupies the atomic doesn't make that task miss the store.
+    let task = spawn({
+        let atomic = atomic.clone();
+        async move { atomic.wait(|x| x == 1).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    assert_eq!(atomic.try_register(&noop_waker()), Err(Occupied));
+    drop(atomic.wait(|x| x == 1));
+    sleep(SMALL_TIMEOUT).await;
+    atomic.store(1);
+    timeout(BIG_TIMEOUT, task).await.unwrap();
+}
+
+#[async_test]
+async fn waker_policy_reject_checked_wait() {
+    use crate::{Occupied, WakerPolicy};
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0).with_waker_policy(WakerPolicy::Reject));
+    let task = spawn({
+        let atomic = atomic.clone();
+        async move { atomic.checked_wait(|x| x == 1).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    // Competing wait fails instead of panicking.
+    assert_eq!(atomic.checked_wait(|x| x == 1).await, Err(Occupied));
+    atomic.store(1);
+    assert_eq!(timeout(BIG_TIMEOUT, task).await.unwrap(), Ok(()));
+    assert!(!atomic.has_waiter());
+}
+
+#[async_test]
+async fn waker_policy_debug_panic() {
+    use crate::{Occupied, WakerPolicy};
+    use futures::task::noop_waker;
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0).with_waker_policy(WakerPolicy::DebugPanic));
+    let task = spawn({
+        let atomic = atomic.clone();
+        async move { atomic.wait(|x| x == 1).await }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    // Competing waker is rejected in debug builds only, in release builds it replaces the waiting one.
+    let result = atomic.try_register(&noop_waker());
+    if cfg!(debug_assertions) {
+        assert_eq!(result, Err(Occupied));
+        atomic.store(1);
+        timeout(BIG_TIMEOUT, task).await.unwrap();
+    } else {
+        assert_eq!(result, Ok(()));
+        atomic.store(1);
+        assert!(timeout(SMALL_TIMEOUT, task).await.is_err());
+    }
+}
+
+#[async_test]
+async fn waker_policy_wake_displaced() {
+    use crate::WakerPolicy;
+
+    let atomic = Arc::new(AsyncAtomic::<u32>::new(0).with_waker_policy(WakerPolicy::WakeDisplaced));
+    let polls = Arc::new(AtomicUsize::new(0));
+    let first = spawn({
+        let (atomic, polls) = (atomic.clone(), polls.clone());
+        async move {
+            poll_fn(|cx| {
+                polls.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+                atomic.poll_wait(cx, |x| x == 1)
+            })
+            .await
+        }
+    });
+    sleep(SMALL_TIMEOUT).await;
+    assert_eq!(polls.load(core::sync::atomic::Ordering::Relaxed), 1);
+    // Displacing the first task wakes it, so it re-polls.
+    assert!(timeout(SMALL_TIMEOUT, atomic.wait(|x| x == 1))
+        .await
+        .is_err());
+    assert!(polls.load(core::sync::atomic::Ordering::Relaxed) >= 2);
+    atomic.store(1);
+    timeout(BIG_TIMEOUT, first).await.unwrap();
+}
+
+#[async_test]
+async fn try_subscribe_exclusive() {
+    let atomic = AsyncAtomic::<u32>::new(0);
+    let mut subscriber = (&atomic).try_subscribe().unwrap();
+    assert!((&atomic).try_subscribe().is_none());
+    atomic.store(1);
+    assert!(subscriber.has_changed());
+    subscriber.mark_seen();
+    assert!(!subscriber.has_changed());
+    assert_eq!(
+        timeout(SMALL_TIMEOUT, subscriber.wait(|x| x == 1))
+            .await
+            .ok(),
+        Some(())
+    );
+    drop(subscriber);
+    assert!((&atomic).try_subscribe().is_some());
+}
+
+#[cfg(feature = "alloc")]
+#[async_test]
+async fn subscriber_token() {
+    let (producer, mut token) = AsyncAtomic::<u32>::new_with_token(0);
+    let task = spawn({
+        let producer = producer.clone();
+        async move {
+            sleep(SMALL_TIMEOUT).await;
+            producer.store(1);
+            sleep(SMALL_TIMEOUT).await;
+            producer.fetch_add(1);
+        }
+    });
+    timeout(BIG_TIMEOUT, token.wait(|x| x == 1)).await.unwrap();
+    let mut changes = token.changed();
+    assert_eq!(changes.next().await, Some(1));
+    assert_eq!(timeout(BIG_TIMEOUT, changes.next()).await.unwrap(), Some(2));
+    task.await;
+    drop(changes);
+    assert_eq!(producer.swap(3), 2);
+    assert_eq!(token.load(), 3);
+}
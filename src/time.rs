@@ -0,0 +1,102 @@
+use crate::{atom_newtype, AsyncAtomic, AsyncAtomicRef, Counter, Wait};
+use core::time::Duration;
+
+/// Tick count of some monotonic clock, e.g. uptime counter of an embedded timer.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(transparent)]
+pub struct Tick(pub u64);
+
+atom_newtype!(Tick(u64), AtomInteger);
+
+impl Counter for Tick {
+    fn delta(self, prev: Self) -> Self {
+        Self(self.0.delta(prev.0))
+    }
+}
+
+/// Async atomic tick counter.
+pub type AsyncAtomicTick = AsyncAtomic<Tick>;
+
+impl AsyncAtomic<Tick> {
+    /// Asynchronously wait for the counter to reach `tick`.
+    pub fn wait_reached(&self, tick: Tick) -> Wait<&Self, impl FnMut(Tick) -> bool> {
+        self.wait(move |x| x >= tick)
+    }
+}
+
+/// Async atomic [`Duration`] stored as a number of nanoseconds in `u64`.
+///
+/// Durations longer than `u64::MAX` nanoseconds (more than 584 years) are saturated.
+#[derive(Default, Debug)]
+pub struct AsyncAtomicDuration {
+    nanos: AsyncAtomic<u64>,
+}
+
+fn to_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl AsyncAtomicDuration {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            nanos: AsyncAtomic::new(to_nanos(duration)),
+        }
+    }
+
+    /// Create atomic in `const` context, e.g. to initialize a `static`.
+    pub const fn zero() -> Self {
+        Self {
+            nanos: AsyncAtomic::<u64>::const_new(0),
+        }
+    }
+
+    /// Underlying atomic number of nanoseconds.
+    pub fn as_nanos(&self) -> &AsyncAtomic<u64> {
+        &self.nanos
+    }
+
+    pub fn load(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load())
+    }
+
+    pub fn store(&self, duration: Duration) {
+        self.nanos.store(to_nanos(duration));
+    }
+
+    pub fn swap(&self, duration: Duration) -> Duration {
+        Duration::from_nanos(self.nanos.swap(to_nanos(duration)))
+    }
+
+    /// Add `duration` saturating on overflow and return the previous value.
+    pub fn fetch_add(&self, duration: Duration) -> Duration {
+        let delta = to_nanos(duration);
+        let (Ok(old) | Err(old)) = self.nanos.fetch_update(|x| Some(x.saturating_add(delta)));
+        Duration::from_nanos(old)
+    }
+
+    /// Subtract `duration` saturating at zero and return the previous value.
+    pub fn fetch_sub(&self, duration: Duration) -> Duration {
+        let delta = to_nanos(duration);
+        let (Ok(old) | Err(old)) = self.nanos.fetch_update(|x| Some(x.saturating_sub(delta)));
+        Duration::from_nanos(old)
+    }
+
+    /// Asynchronously wait for the duration to become at least `threshold`.
+    pub fn wait_at_least(
+        &self,
+        threshold: Duration,
+    ) -> Wait<&AsyncAtomic<u64>, impl FnMut(u64) -> bool> {
+        let threshold = to_nanos(threshold);
+        self.nanos.wait(move |x| x >= threshold)
+    }
+
+    /// Asynchronously wait for the duration to become less than `threshold`.
+    pub fn wait_below(
+        &self,
+        threshold: Duration,
+    ) -> Wait<&AsyncAtomic<u64>, impl FnMut(u64) -> bool> {
+        let threshold = to_nanos(threshold);
+        self.nanos.wait(move |x| x < threshold)
+    }
+}
@@ -0,0 +1,77 @@
+use core::{
+    fmt,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use pin_project_lite::pin_project;
+
+/// Source of delays for time-based features.
+///
+/// The crate doesn't depend on any particular runtime, so the sleep is provided by user.
+/// Any `Fn(Duration) -> impl Future<Output = ()>` is a timer,
+/// e.g. `&async_std::task::sleep` or `&tokio::time::sleep` can be passed directly.
+pub trait Timer {
+    /// Future that completes after the duration passed.
+    type Sleep: Future<Output = ()>;
+
+    /// Create a future that completes after `duration`.
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+}
+
+impl<S: Future<Output = ()>, F: Fn(Duration) -> S> Timer for F {
+    type Sleep = S;
+
+    fn sleep(&self, duration: Duration) -> S {
+        self(duration)
+    }
+}
+
+/// Error returned when a future hasn't completed in time.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Elapsed;
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Elapsed {}
+
+pin_project! {
+    /// Future that resolves with `Err(Elapsed)` if inner future hasn't completed before the sleep.
+    pub struct Timeout<F, S> {
+        #[pin]
+        pub future: F,
+        #[pin]
+        pub sleep: S,
+    }
+}
+
+impl<F: Future, S: Future<Output = ()>> Future for Timeout<F, S> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Poll::Ready(x) = this.future.poll(cx) {
+            return Poll::Ready(Ok(x));
+        }
+        this.sleep.poll(cx).map(|()| Err(Elapsed))
+    }
+}
+
+/// Run `future` until completion or until `duration` passed on `timer`, whichever is first.
+pub fn timeout<F: IntoFuture, T: Timer + ?Sized>(
+    timer: &T,
+    duration: Duration,
+    future: F,
+) -> Timeout<F::IntoFuture, T::Sleep> {
+    Timeout {
+        future: future.into_future(),
+        sleep: timer.sleep(duration),
+    }
+}
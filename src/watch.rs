@@ -0,0 +1,36 @@
+use crate::AsyncAtomicRef;
+use futures::stream::StreamExt;
+use tokio::sync::watch;
+
+/// Store every value received from `tokio::sync::watch` channel into atomic.
+///
+/// The current value of the channel is stored immediately.
+/// Resolves when the sender is dropped.
+pub async fn forward_from_watch<R: AsyncAtomicRef<Item: Clone>>(
+    mut rx: watch::Receiver<R::Item>,
+    atomic: R,
+) {
+    loop {
+        let value = rx.borrow_and_update().clone();
+        atomic.as_atomic().store(value);
+        if rx.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Send every change of atomic into `tokio::sync::watch` channel.
+///
+/// The current value of the atomic is sent immediately.
+/// Resolves when all receivers are dropped (this is detected on the next change of the atomic).
+pub async fn forward_to_watch<R: AsyncAtomicRef<Item: PartialEq + Clone>>(
+    atomic: R,
+    tx: watch::Sender<R::Item>,
+) {
+    let mut changes = atomic.changed();
+    while let Some(value) = changes.next().await {
+        if tx.send(value).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{timeout, AsyncAtomic, AsyncAtomicRef, Timer};
+use core::time::Duration;
+
+/// Heartbeat watchdog for supervising tasks.
+///
+/// Supervised tasks [`pet`](`Self::pet`) the watchdog periodically
+/// and the supervisor waits for it to become [stale](`Self::wait_stale`).
+#[derive(Default, Debug)]
+pub struct Watchdog {
+    pets: AsyncAtomic<usize>,
+}
+
+impl Watchdog {
+    pub const fn new() -> Self {
+        Self {
+            pets: AsyncAtomic::<usize>::const_new(0),
+        }
+    }
+
+    /// Signal that the supervised task is alive.
+    pub fn pet(&self) {
+        self.pets.fetch_add(1);
+    }
+
+    /// Total number of pets so far (wrapping on overflow).
+    pub fn pets(&self) -> usize {
+        self.pets.load()
+    }
+
+    /// Asynchronously wait until no pet arrives within `window`.
+    ///
+    /// The window is restarted on every pet.
+    pub async fn wait_stale<T: Timer + ?Sized>(&self, timer: &T, window: Duration) {
+        loop {
+            let seen = self.pets.load();
+            if timeout(timer, window, self.pets.wait(|x| x != seen))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+}
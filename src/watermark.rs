@@ -0,0 +1,106 @@
+use crate::{AsyncAtomic, AsyncAtomicRef, Wait};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Current level (e.g. queue depth or buffer usage) together with its running low and high watermarks.
+///
+/// Threshold waits consult the watermarks, so a peak that rises and falls before the waiter is polled is not missed.
+#[derive(Debug)]
+pub struct Watermark {
+    level: AsyncAtomic<usize>,
+    low: AtomicUsize,
+    high: AtomicUsize,
+}
+
+impl Default for Watermark {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Watermark {
+    pub fn new(level: usize) -> Self {
+        Self {
+            level: AsyncAtomic::new(level),
+            low: AtomicUsize::new(level),
+            high: AtomicUsize::new(level),
+        }
+    }
+
+    /// Underlying atomic level.
+    pub fn level(&self) -> &AsyncAtomic<usize> {
+        &self.level
+    }
+
+    /// Current level.
+    pub fn load(&self) -> usize {
+        self.level.load()
+    }
+
+    /// Minimal level observed since creation or the last [`reset`](`Self::reset`).
+    pub fn low(&self) -> usize {
+        self.low.load(Ordering::Relaxed)
+    }
+
+    /// Maximal level observed since creation or the last [`reset`](`Self::reset`).
+    pub fn high(&self) -> usize {
+        self.high.load(Ordering::Relaxed)
+    }
+
+    fn track(&self, level: usize) {
+        self.low.fetch_min(level, Ordering::Relaxed);
+        self.high.fetch_max(level, Ordering::Relaxed);
+    }
+
+    /// Set the level.
+    pub fn store(&self, level: usize) {
+        self.track(level);
+        self.level.store(level);
+    }
+
+    /// Increase the level by `n` (saturating) and return the new level.
+    pub fn add(&self, n: usize) -> usize {
+        self.update(|x| x.saturating_add(n))
+    }
+
+    /// Decrease the level by `n` (saturating at zero) and return the new level.
+    pub fn sub(&self, n: usize) -> usize {
+        self.update(|x| x.saturating_sub(n))
+    }
+
+    fn update<F: Fn(usize) -> usize>(&self, f: F) -> usize {
+        // Update watermarks before waking the subscriber.
+        let (Ok(old) | Err(old)) =
+            self.level
+                .value
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |x| Some(f(x)));
+        let level = f(old);
+        self.track(level);
+        self.level.notify();
+        level
+    }
+
+    /// Reset watermarks to the current level.
+    pub fn reset(&self) {
+        let level = self.level.load();
+        self.low.store(level, Ordering::Relaxed);
+        self.high.store(level, Ordering::Relaxed);
+    }
+
+    /// Asynchronously wait until the level has been above `threshold` since creation or the last [`reset`](`Self::reset`).
+    pub fn wait_above(
+        &self,
+        threshold: usize,
+    ) -> Wait<&AsyncAtomic<usize>, impl FnMut(usize) -> bool + '_> {
+        self.level
+            .wait(move |x| x > threshold || self.high.load(Ordering::Relaxed) > threshold)
+    }
+
+    /// Asynchronously wait until the level has been below `threshold` since creation or the last [`reset`](`Self::reset`).
+    pub fn wait_below(
+        &self,
+        threshold: usize,
+    ) -> Wait<&AsyncAtomic<usize>, impl FnMut(usize) -> bool + '_> {
+        self.level
+            .wait(move |x| x < threshold || self.low.load(Ordering::Relaxed) < threshold)
+    }
+}
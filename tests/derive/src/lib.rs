@@ -0,0 +1,60 @@
+#![cfg(test)]
+
+use async_atomic::{atomig, AsyncAtomic, AsyncAtomicRef, Atom, AtomInteger, DynAsyncAtomic};
+use async_std::{stream::StreamExt, task::block_on};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[derive(Atom, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+enum Mode {
+    Off,
+    Standby,
+    On = 7,
+}
+
+#[derive(Atom, Clone, Copy, PartialEq, Eq, Debug)]
+struct Count(u32);
+impl AtomInteger for Count {}
+
+#[test]
+fn enum_round_trip() {
+    for mode in [Mode::Off, Mode::Standby, Mode::On] {
+        assert_eq!(Mode::unpack(mode.pack()), mode);
+    }
+}
+
+#[test]
+fn enum_invalid_bits() {
+    assert!(catch_unwind(|| Mode::unpack(3)).is_err());
+
+    let mode = AsyncAtomic::new(Mode::Standby);
+    let erased: &dyn DynAsyncAtomic = &mode;
+    assert!(catch_unwind(AssertUnwindSafe(|| erased.store_raw(3))).is_err());
+    assert_eq!(mode.load(), Mode::Standby);
+    erased.store_raw(7);
+    assert_eq!(mode.load(), Mode::On);
+}
+
+#[test]
+fn enum_wait() {
+    let mode = AsyncAtomic::new(Mode::Off);
+    let mut changes = (&mode).changed();
+    assert_eq!(block_on(changes.next()), Some(Mode::Off));
+    mode.store(Mode::Standby);
+    assert_eq!(block_on(changes.next()), Some(Mode::Standby));
+    drop(changes);
+    assert_eq!(mode.transition(Mode::Standby, Mode::On), Ok(()));
+    block_on(mode.wait_state(Mode::On));
+}
+
+#[test]
+fn newtype_fetch() {
+    let count = AsyncAtomic::new(Count(1));
+    assert_eq!(count.fetch_add(Count(2)), Count(1));
+    block_on(count.wait(|Count(x)| x == 3));
+    assert_eq!(
+        count.fetch_update(|Count(x)| Some(Count(x * 2))),
+        Ok(Count(3))
+    );
+    assert_eq!(count.load(), Count(6));
+}